@@ -0,0 +1,493 @@
+use std::sync::Mutex;
+
+use futures::future::{BoxFuture, FutureExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    http::HttpClient,
+    lnurl::{self, LightningAddress},
+    PayoutError,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub payment_hash: String,
+    pub sats: u64,
+}
+
+// anything that can pay a bolt11 invoice out of the game's wallet
+pub trait PayoutBackend: Send + Sync {
+    fn pay_invoice<'a>(
+        &'a self,
+        bolt11: &'a str,
+        sats: u64,
+    ) -> BoxFuture<'a, Result<Receipt, PayoutError>>;
+}
+
+// records invoices instead of paying them, for local runs and tests
+#[derive(Default)]
+pub struct MockBackend {
+    pub paid: Mutex<Vec<String>>,
+    pub fail_with: Option<String>,
+}
+
+impl PayoutBackend for MockBackend {
+    fn pay_invoice<'a>(
+        &'a self,
+        bolt11: &'a str,
+        sats: u64,
+    ) -> BoxFuture<'a, Result<Receipt, PayoutError>> {
+        let result = match &self.fail_with {
+            Some(reason) => Err(PayoutError::Backend(reason.clone())),
+            None => {
+                let mut paid = self.paid.lock().unwrap();
+                paid.push(bolt11.to_string());
+                Ok(Receipt {
+                    payment_hash: format!("mock-{}", paid.len()),
+                    sats,
+                })
+            }
+        };
+
+        async move { result }.boxed()
+    }
+}
+
+// turns a winner's lightning address into an invoice for `sats`
+pub trait InvoiceResolver: Send + Sync {
+    fn resolve_invoice<'a>(
+        &'a self,
+        address: &'a LightningAddress,
+        sats: u64,
+    ) -> BoxFuture<'a, Result<String, PayoutError>>;
+}
+
+// LUD-16 lookup then the LUD-06 callback, `endpoint` works like LightningAddress::lnurlp_url
+pub struct LnurlResolver<H: HttpClient> {
+    pub http: H,
+    pub endpoint: String,
+}
+
+impl<H: HttpClient> InvoiceResolver for LnurlResolver<H> {
+    fn resolve_invoice<'a>(
+        &'a self,
+        address: &'a LightningAddress,
+        sats: u64,
+    ) -> BoxFuture<'a, Result<String, PayoutError>> {
+        async move {
+            let params = lnurl::fetch_pay_params(&self.http, address, &self.endpoint).await?;
+            Ok(lnurl::request_invoice(&self.http, &params, sats * 1000).await?)
+        }
+        .boxed()
+    }
+}
+
+// makes up an invoice per lookup instead of asking the wallet, for local runs and tests
+#[derive(Default)]
+pub struct MockResolver {
+    pub resolved: Mutex<Vec<String>>,
+    pub fail_with: Option<PayoutError>,
+}
+
+impl InvoiceResolver for MockResolver {
+    fn resolve_invoice<'a>(
+        &'a self,
+        address: &'a LightningAddress,
+        sats: u64,
+    ) -> BoxFuture<'a, Result<String, PayoutError>> {
+        let result = match &self.fail_with {
+            Some(e) => Err(e.clone()),
+            None => {
+                let mut resolved = self.resolved.lock().unwrap();
+                resolved.push(address.to_string());
+                Ok(format!("lnmock{}n1{}", sats, resolved.len()))
+            }
+        };
+
+        async move { result }.boxed()
+    }
+}
+
+// LNbits wallet api. LND's own REST api (/v1/channels/transactions with a macaroon header)
+// isn't supported, run LNbits in front of the node instead
+pub struct LnbitsBackend<H: HttpClient> {
+    pub http: H,
+    pub url: String,
+    pub admin_key: String,
+}
+
+impl<H: HttpClient> LnbitsBackend<H> {
+    fn payments_url(&self) -> String {
+        format!("{}/api/v1/payments", self.url.trim_end_matches('/'))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, PayoutError> {
+        let body = self
+            .http
+            .get(url, &[("X-Api-Key", &self.admin_key)])
+            .await
+            .map_err(PayoutError::Backend)?;
+
+        serde_json::from_str(&body).map_err(|e| PayoutError::Backend(e.to_string()))
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        request: &impl Serialize,
+    ) -> Result<T, PayoutError> {
+        let request =
+            serde_json::to_string(request).map_err(|e| PayoutError::Backend(e.to_string()))?;
+        let body = self
+            .http
+            .post(url, &[("X-Api-Key", &self.admin_key)], request)
+            .await
+            .map_err(PayoutError::Backend)?;
+
+        serde_json::from_str(&body).map_err(|e| PayoutError::Backend(e.to_string()))
+    }
+}
+
+#[derive(Serialize)]
+struct PayRequest<'a> {
+    out: bool,
+    bolt11: &'a str,
+}
+
+#[derive(Deserialize)]
+struct PayResponse {
+    payment_hash: Option<String>,
+    detail: Option<String>,
+}
+
+impl<H: HttpClient> PayoutBackend for LnbitsBackend<H> {
+    fn pay_invoice<'a>(
+        &'a self,
+        bolt11: &'a str,
+        sats: u64,
+    ) -> BoxFuture<'a, Result<Receipt, PayoutError>> {
+        async move {
+            let response: PayResponse = self
+                .post(&self.payments_url(), &PayRequest { out: true, bolt11 })
+                .await?;
+
+            match response {
+                PayResponse {
+                    payment_hash: Some(payment_hash),
+                    ..
+                } => Ok(Receipt { payment_hash, sats }),
+                PayResponse { detail, .. } => Err(PayoutError::Backend(
+                    detail.unwrap_or_else(|| "payment failed".to_string()),
+                )),
+            }
+        }
+        .boxed()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Invoice {
+    pub bolt11: String,
+    pub payment_hash: String,
+    pub sats: u64,
+}
+
+// the receiving side, used to charge tournament entry fees
+pub trait InvoiceBackend: Send + Sync {
+    // the wallet stops accepting the invoice after `expiry_secs`
+    fn create_invoice<'a>(
+        &'a self,
+        sats: u64,
+        memo: &'a str,
+        expiry_secs: u64,
+    ) -> BoxFuture<'a, Result<Invoice, PayoutError>>;
+
+    fn is_paid<'a>(&'a self, payment_hash: &'a str) -> BoxFuture<'a, Result<bool, PayoutError>>;
+}
+
+// hands out fake invoices, `settle` marks one paid as if a wallet had paid it
+#[derive(Default)]
+pub struct MockInvoices {
+    pub issued: Mutex<Vec<Invoice>>,
+    pub settled: Mutex<Vec<String>>,
+}
+
+impl MockInvoices {
+    pub fn settle(&self, payment_hash: &str) {
+        self.settled.lock().unwrap().push(payment_hash.to_string());
+    }
+}
+
+impl InvoiceBackend for MockInvoices {
+    fn create_invoice<'a>(
+        &'a self,
+        sats: u64,
+        memo: &'a str,
+        _expiry_secs: u64,
+    ) -> BoxFuture<'a, Result<Invoice, PayoutError>> {
+        let mut issued = self.issued.lock().unwrap();
+        let invoice = Invoice {
+            bolt11: format!("lnmock{}n1{}", sats, memo.replace(' ', "")),
+            payment_hash: format!("mock-invoice-{}", issued.len() + 1),
+            sats,
+        };
+        issued.push(invoice.clone());
+
+        async move { Ok(invoice) }.boxed()
+    }
+
+    fn is_paid<'a>(&'a self, payment_hash: &'a str) -> BoxFuture<'a, Result<bool, PayoutError>> {
+        let paid = self
+            .settled
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|settled| settled == payment_hash);
+
+        async move { Ok(paid) }.boxed()
+    }
+}
+
+#[derive(Serialize)]
+struct InvoiceRequest<'a> {
+    out: bool,
+    amount: u64,
+    memo: &'a str,
+    expiry: u64,
+}
+
+#[derive(Deserialize)]
+struct InvoiceResponse {
+    payment_hash: String,
+    payment_request: String,
+}
+
+#[derive(Deserialize)]
+struct PaymentStatus {
+    paid: bool,
+}
+
+impl<H: HttpClient> InvoiceBackend for LnbitsBackend<H> {
+    fn create_invoice<'a>(
+        &'a self,
+        sats: u64,
+        memo: &'a str,
+        expiry_secs: u64,
+    ) -> BoxFuture<'a, Result<Invoice, PayoutError>> {
+        async move {
+            let request = InvoiceRequest {
+                out: false,
+                amount: sats,
+                memo,
+                expiry: expiry_secs,
+            };
+            let response: InvoiceResponse = self.post(&self.payments_url(), &request).await?;
+
+            Ok(Invoice {
+                bolt11: response.payment_request,
+                payment_hash: response.payment_hash,
+                sats,
+            })
+        }
+        .boxed()
+    }
+
+    fn is_paid<'a>(&'a self, payment_hash: &'a str) -> BoxFuture<'a, Result<bool, PayoutError>> {
+        async move {
+            let url = format!("{}/{}", self.payments_url(), payment_hash);
+            let status: PaymentStatus = self.get(&url).await?;
+
+            Ok(status.paid)
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    struct Sent {
+        url: String,
+        headers: Vec<(String, String)>,
+        body: Option<String>,
+    }
+
+    // answers each request with the next canned body and remembers what was asked
+    #[derive(Default)]
+    struct CannedHttp {
+        responses: Mutex<VecDeque<Result<String, String>>>,
+        requests: Mutex<Vec<Sent>>,
+    }
+
+    impl CannedHttp {
+        fn new(responses: &[Result<&str, &str>]) -> Self {
+            Self {
+                responses: Mutex::new(
+                    responses
+                        .iter()
+                        .map(|response| response.map(String::from).map_err(String::from))
+                        .collect(),
+                ),
+                ..Self::default()
+            }
+        }
+
+        fn respond(
+            &self,
+            url: &str,
+            headers: &[(&str, &str)],
+            body: Option<String>,
+        ) -> BoxFuture<'_, Result<String, String>> {
+            let headers = headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            self.requests.lock().unwrap().push(Sent {
+                url: url.to_string(),
+                headers,
+                body,
+            });
+            let response = self.responses.lock().unwrap().pop_front().unwrap();
+
+            async move { response }.boxed()
+        }
+    }
+
+    impl HttpClient for CannedHttp {
+        fn get<'a>(
+            &'a self,
+            url: &'a str,
+            headers: &'a [(&'a str, &'a str)],
+        ) -> BoxFuture<'a, Result<String, String>> {
+            self.respond(url, headers, None)
+        }
+
+        fn post<'a>(
+            &'a self,
+            url: &'a str,
+            headers: &'a [(&'a str, &'a str)],
+            body: String,
+        ) -> BoxFuture<'a, Result<String, String>> {
+            self.respond(url, headers, Some(body))
+        }
+    }
+
+    fn lnbits(responses: &[Result<&str, &str>]) -> LnbitsBackend<CannedHttp> {
+        LnbitsBackend {
+            http: CannedHttp::new(responses),
+            url: "https://lnbits.example.com/".to_string(),
+            admin_key: "admin".to_string(),
+        }
+    }
+
+    #[test]
+    fn lnbits_pays_invoice() {
+        let backend = lnbits(&[Ok(r#"{"payment_hash": "abc", "checking_id": "abc"}"#)]);
+
+        let receipt = block_on(backend.pay_invoice("lnbc1", 21)).unwrap();
+
+        assert_eq!(
+            receipt,
+            Receipt {
+                payment_hash: "abc".to_string(),
+                sats: 21,
+            }
+        );
+        let requests = backend.http.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].url,
+            "https://lnbits.example.com/api/v1/payments"
+        );
+        assert_eq!(
+            requests[0].headers,
+            [("X-Api-Key".to_string(), "admin".to_string())]
+        );
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some(r#"{"out":true,"bolt11":"lnbc1"}"#)
+        );
+    }
+
+    #[test]
+    fn lnbits_surfaces_payment_errors() {
+        let backend = lnbits(&[
+            Ok(r#"{"detail": "Insufficient balance."}"#),
+            Err("connection refused"),
+        ]);
+
+        assert_eq!(
+            block_on(backend.pay_invoice("lnbc1", 21)),
+            Err(PayoutError::Backend("Insufficient balance.".to_string()))
+        );
+        assert_eq!(
+            block_on(backend.pay_invoice("lnbc1", 21)),
+            Err(PayoutError::Backend("connection refused".to_string()))
+        );
+    }
+
+    #[test]
+    fn lnbits_creates_and_checks_invoices() {
+        let backend = lnbits(&[
+            Ok(r#"{"payment_hash": "abc", "payment_request": "lnbc100n1"}"#),
+            Ok(r#"{"paid": true}"#),
+        ]);
+
+        let invoice = block_on(backend.create_invoice(100, "entry", 600)).unwrap();
+        let paid = block_on(backend.is_paid(&invoice.payment_hash)).unwrap();
+
+        assert_eq!(
+            invoice,
+            Invoice {
+                bolt11: "lnbc100n1".to_string(),
+                payment_hash: "abc".to_string(),
+                sats: 100,
+            }
+        );
+        assert!(paid);
+        let requests = backend.http.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some(r#"{"out":false,"amount":100,"memo":"entry","expiry":600}"#)
+        );
+        assert_eq!(
+            requests[1].url,
+            "https://lnbits.example.com/api/v1/payments/abc"
+        );
+    }
+
+    #[test]
+    fn lnurl_resolver_asks_the_callback() {
+        let resolver = LnurlResolver {
+            http: CannedHttp::new(&[
+                Ok(r#"{
+                    "callback": "https://example.com/callback?id=1",
+                    "minSendable": 1000,
+                    "maxSendable": 100000,
+                    "metadata": "[]",
+                    "tag": "payRequest"
+                }"#),
+                Ok(r#"{"pr": "lnbc210n1", "routes": []}"#),
+            ]),
+            endpoint: String::new(),
+        };
+        let address = LightningAddress::parse("alice@example.com").unwrap();
+
+        let invoice = block_on(resolver.resolve_invoice(&address, 21)).unwrap();
+
+        assert_eq!(invoice, "lnbc210n1");
+        let requests = resolver.http.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].url,
+            "https://example.com/.well-known/lnurlp/alice"
+        );
+        assert_eq!(
+            requests[1].url,
+            "https://example.com/callback?id=1&amount=21000"
+        );
+    }
+}
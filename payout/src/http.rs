@@ -0,0 +1,76 @@
+use futures::future::BoxFuture;
+
+// the bits of http the wallets need, so the server can bring its own client and tests can
+// answer without a network
+pub trait HttpClient: Send + Sync {
+    // response body, or why there wasn't one
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+    ) -> BoxFuture<'a, Result<String, String>>;
+
+    // `body` is sent as json
+    fn post<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a [(&'a str, &'a str)],
+        body: String,
+    ) -> BoxFuture<'a, Result<String, String>>;
+}
+
+#[cfg(feature = "reqwest")]
+pub use reqwest_client::ReqwestClient;
+
+#[cfg(feature = "reqwest")]
+mod reqwest_client {
+    use futures::future::{BoxFuture, FutureExt};
+
+    use super::HttpClient;
+
+    #[derive(Default, Clone)]
+    pub struct ReqwestClient(pub reqwest::Client);
+
+    async fn read_body(request: reqwest::RequestBuilder) -> Result<String, String> {
+        request
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    impl HttpClient for ReqwestClient {
+        fn get<'a>(
+            &'a self,
+            url: &'a str,
+            headers: &'a [(&'a str, &'a str)],
+        ) -> BoxFuture<'a, Result<String, String>> {
+            let request = headers
+                .iter()
+                .fold(self.0.get(url), |request, (name, value)| {
+                    request.header(*name, *value)
+                });
+
+            read_body(request).boxed()
+        }
+
+        fn post<'a>(
+            &'a self,
+            url: &'a str,
+            headers: &'a [(&'a str, &'a str)],
+            body: String,
+        ) -> BoxFuture<'a, Result<String, String>> {
+            let request = headers
+                .iter()
+                .fold(self.0.post(url), |request, (name, value)| {
+                    request.header(*name, *value)
+                })
+                .header("Content-Type", "application/json")
+                .body(body);
+
+            read_body(request).boxed()
+        }
+    }
+}
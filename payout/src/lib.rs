@@ -0,0 +1,399 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod backend;
+pub mod http;
+pub mod lnurl;
+pub mod tournament;
+
+use backend::{InvoiceResolver, PayoutBackend, Receipt};
+use lnurl::{LightningAddress, LnurlError};
+
+pub const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoutError {
+    Lnurl(LnurlError),
+    OverPaymentLimit(u64),
+    OverAddressLimit(u64),
+    OverDailyLimit(u64),
+    Backend(String),
+}
+
+impl fmt::Display for PayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayoutError::Lnurl(e) => write!(f, "{}", e),
+            PayoutError::OverPaymentLimit(limit) => {
+                write!(f, "reward is over the {} sat payment limit", limit)
+            }
+            PayoutError::OverAddressLimit(limit) => {
+                write!(f, "address already got its {} sats for today", limit)
+            }
+            PayoutError::OverDailyLimit(limit) => {
+                write!(f, "today's {} sat budget is spent", limit)
+            }
+            PayoutError::Backend(e) => write!(f, "payment failed: {}", e),
+        }
+    }
+}
+
+impl From<LnurlError> for PayoutError {
+    fn from(e: LnurlError) -> Self {
+        PayoutError::Lnurl(e)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpendingLimits {
+    pub per_payment_sats: u64,
+    pub per_address_daily_sats: u64,
+    pub daily_sats: u64,
+}
+
+impl Default for SpendingLimits {
+    fn default() -> Self {
+        Self {
+            per_payment_sats: 1_000,
+            per_address_daily_sats: 5_000,
+            daily_sats: 100_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Reward {
+    // the same key is never paid twice, so retries after a dropped connection are safe
+    pub key: String,
+    pub address: LightningAddress,
+    pub sats: u64,
+}
+
+impl Reward {
+    pub fn bolt(player: Uuid, tick: u64, address: LightningAddress, sats: u64) -> Self {
+        Self {
+            key: format!("bolt:{}:{}", player, tick),
+            address,
+            sats,
+        }
+    }
+
+    // `mode` is the game's name for the mode the run was won in
+    pub fn win(
+        player: Uuid,
+        mode: &str,
+        finish_tick: u64,
+        address: LightningAddress,
+        sats: u64,
+    ) -> Self {
+        Self {
+            key: format!("win:{}:{}:{}", mode, player, finish_tick),
+            address,
+            sats,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum AuditOutcome {
+    Paid(Receipt),
+    Duplicate,
+    Refused(String),
+    Failed(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub time: u64,
+    pub key: String,
+    pub address: String,
+    pub sats: u64,
+    pub outcome: AuditOutcome,
+}
+
+pub struct Payouts<B: PayoutBackend, R: InvoiceResolver> {
+    backend: B,
+    resolver: R,
+    pub limits: SpendingLimits,
+    paid: HashMap<String, Receipt>,
+    // day number -> sats spent, per address and in total
+    spent_by_address: HashMap<(u64, String), u64>,
+    spent_by_day: HashMap<u64, u64>,
+    audit: Vec<AuditEntry>,
+}
+
+impl<B: PayoutBackend, R: InvoiceResolver> Payouts<B, R> {
+    // `audit` is the log the server saved last run, empty on a fresh start
+    pub fn new(backend: B, resolver: R, limits: SpendingLimits, audit: Vec<AuditEntry>) -> Self {
+        let mut payouts = Self {
+            backend,
+            resolver,
+            limits,
+            paid: HashMap::new(),
+            spent_by_address: HashMap::new(),
+            spent_by_day: HashMap::new(),
+            audit: Vec::new(),
+        };
+
+        // rebuild idempotency and spending state from the log after a restart
+        for entry in audit.iter() {
+            if let AuditOutcome::Paid(receipt) = &entry.outcome {
+                payouts.record_spend(entry.time, &entry.address, receipt.sats);
+                payouts.paid.insert(entry.key.clone(), receipt.clone());
+            }
+        }
+        payouts.audit = audit;
+
+        payouts
+    }
+
+    // the server persists this after every `pay`
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.audit
+    }
+
+    pub fn spent_today(&self, now: u64) -> u64 {
+        self.spent_by_day
+            .get(&(now / SECS_PER_DAY))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    // `now` is unix seconds, passed in so the limits are easy to exercise
+    pub async fn pay(&mut self, reward: Reward, now: u64) -> Result<Receipt, PayoutError> {
+        if let Some(receipt) = self.paid.get(&reward.key).cloned() {
+            self.log(&reward, now, AuditOutcome::Duplicate);
+            return Ok(receipt);
+        }
+
+        if let Err(e) = self.check_limits(&reward, now) {
+            self.log(&reward, now, AuditOutcome::Refused(e.to_string()));
+            return Err(e);
+        }
+
+        let result = self.send(&reward).await;
+
+        match &result {
+            Ok(receipt) => {
+                self.record_spend(now, &reward.address.to_string(), receipt.sats);
+                self.paid.insert(reward.key.clone(), receipt.clone());
+                self.log(&reward, now, AuditOutcome::Paid(receipt.clone()));
+            }
+            Err(e) => self.log(&reward, now, AuditOutcome::Failed(e.to_string())),
+        }
+
+        result
+    }
+
+    async fn send(&self, reward: &Reward) -> Result<Receipt, PayoutError> {
+        let invoice = self
+            .resolver
+            .resolve_invoice(&reward.address, reward.sats)
+            .await?;
+        self.backend.pay_invoice(&invoice, reward.sats).await
+    }
+
+    fn check_limits(&self, reward: &Reward, now: u64) -> Result<(), PayoutError> {
+        let day = now / SECS_PER_DAY;
+        let address = reward.address.to_string();
+        let by_address = self
+            .spent_by_address
+            .get(&(day, address))
+            .copied()
+            .unwrap_or(0);
+
+        if reward.sats > self.limits.per_payment_sats {
+            Err(PayoutError::OverPaymentLimit(self.limits.per_payment_sats))
+        } else if by_address + reward.sats > self.limits.per_address_daily_sats {
+            Err(PayoutError::OverAddressLimit(
+                self.limits.per_address_daily_sats,
+            ))
+        } else if self.spent_today(now) + reward.sats > self.limits.daily_sats {
+            Err(PayoutError::OverDailyLimit(self.limits.daily_sats))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn record_spend(&mut self, now: u64, address: &str, sats: u64) {
+        let day = now / SECS_PER_DAY;
+        *self
+            .spent_by_address
+            .entry((day, address.to_string()))
+            .or_insert(0) += sats;
+        *self.spent_by_day.entry(day).or_insert(0) += sats;
+    }
+
+    fn log(&mut self, reward: &Reward, now: u64, outcome: AuditOutcome) {
+        self.audit.push(AuditEntry {
+            time: now,
+            key: reward.key.clone(),
+            address: reward.address.to_string(),
+            sats: reward.sats,
+            outcome,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use backend::{MockBackend, MockResolver};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn payouts(limits: SpendingLimits) -> Payouts<MockBackend, MockResolver> {
+        Payouts::new(
+            MockBackend::default(),
+            MockResolver::default(),
+            limits,
+            Vec::new(),
+        )
+    }
+
+    fn address(user: &str) -> LightningAddress {
+        LightningAddress::parse(&format!("{}@example.com", user)).unwrap()
+    }
+
+    fn reward(tick: u64, user: &str, sats: u64) -> Reward {
+        Reward::bolt(Uuid::from_u128(1), tick, address(user), sats)
+    }
+
+    #[test]
+    fn pays_and_logs_reward() {
+        let mut payouts = payouts(SpendingLimits::default());
+
+        let receipt = block_on(payouts.pay(reward(1, "alice", 21), NOW)).unwrap();
+
+        assert_eq!(receipt.sats, 21);
+        assert_eq!(
+            payouts.backend.paid.lock().unwrap().as_slice(),
+            ["lnmock21n11"]
+        );
+        assert_eq!(payouts.spent_today(NOW), 21);
+        assert_eq!(
+            payouts.audit_log(),
+            [AuditEntry {
+                time: NOW,
+                key: format!("bolt:{}:1", Uuid::from_u128(1)),
+                address: "alice@example.com".to_string(),
+                sats: 21,
+                outcome: AuditOutcome::Paid(receipt),
+            }]
+        );
+    }
+
+    #[test]
+    fn payouts_can_run_on_a_threaded_runtime() {
+        fn assert_send<T: Send>(_: T) {}
+
+        let mut payouts = payouts(SpendingLimits::default());
+        assert_send(payouts.pay(reward(1, "alice", 21), NOW));
+    }
+
+    #[test]
+    fn refuses_payment_over_limit() {
+        let mut payouts = payouts(SpendingLimits {
+            per_payment_sats: 100,
+            ..SpendingLimits::default()
+        });
+
+        let result = block_on(payouts.pay(reward(1, "alice", 101), NOW));
+
+        assert_eq!(result, Err(PayoutError::OverPaymentLimit(100)));
+        assert!(payouts.resolver.resolved.lock().unwrap().is_empty());
+        assert!(payouts.backend.paid.lock().unwrap().is_empty());
+        assert_eq!(payouts.spent_today(NOW), 0);
+        assert!(matches!(
+            payouts.audit_log()[0].outcome,
+            AuditOutcome::Refused(_)
+        ));
+    }
+
+    #[test]
+    fn refuses_over_address_and_daily_limits() {
+        let mut payouts = payouts(SpendingLimits {
+            per_payment_sats: 100,
+            per_address_daily_sats: 150,
+            daily_sats: 250,
+        });
+
+        assert!(block_on(payouts.pay(reward(1, "alice", 100), NOW)).is_ok());
+        assert_eq!(
+            block_on(payouts.pay(reward(2, "alice", 100), NOW)),
+            Err(PayoutError::OverAddressLimit(150))
+        );
+        assert!(block_on(payouts.pay(reward(3, "bob", 100), NOW)).is_ok());
+        assert_eq!(
+            block_on(payouts.pay(reward(4, "carol", 100), NOW)),
+            Err(PayoutError::OverDailyLimit(250))
+        );
+        assert_eq!(payouts.spent_today(NOW), 200);
+
+        // the budgets start over the next day
+        let tomorrow = NOW + SECS_PER_DAY;
+        assert!(block_on(payouts.pay(reward(5, "alice", 100), tomorrow)).is_ok());
+        assert_eq!(payouts.spent_today(tomorrow), 100);
+    }
+
+    #[test]
+    fn pays_each_key_once() {
+        let mut payouts = payouts(SpendingLimits::default());
+
+        let first = block_on(payouts.pay(reward(1, "alice", 21), NOW)).unwrap();
+        let retry = block_on(payouts.pay(reward(1, "alice", 21), NOW + 5)).unwrap();
+
+        assert_eq!(first, retry);
+        assert_eq!(payouts.backend.paid.lock().unwrap().len(), 1);
+        assert_eq!(payouts.spent_today(NOW), 21);
+        assert_eq!(payouts.audit_log()[1].outcome, AuditOutcome::Duplicate);
+    }
+
+    #[test]
+    fn logs_failed_payments() {
+        let mut payouts = Payouts::new(
+            MockBackend::default(),
+            MockResolver {
+                fail_with: Some(PayoutError::Lnurl(LnurlError::NotPayRequest)),
+                ..MockResolver::default()
+            },
+            SpendingLimits::default(),
+            Vec::new(),
+        );
+
+        let result = block_on(payouts.pay(reward(1, "alice", 21), NOW));
+
+        assert_eq!(result, Err(PayoutError::Lnurl(LnurlError::NotPayRequest)));
+        assert_eq!(payouts.spent_today(NOW), 0);
+        assert_eq!(
+            payouts.audit_log()[0].outcome,
+            AuditOutcome::Failed("address can't receive payments".to_string())
+        );
+
+        // a failed key can be tried again
+        payouts.resolver.fail_with = None;
+        assert!(block_on(payouts.pay(reward(1, "alice", 21), NOW)).is_ok());
+    }
+
+    #[test]
+    fn restores_state_from_audit_log() {
+        let mut before = payouts(SpendingLimits::default());
+        block_on(before.pay(reward(1, "alice", 21), NOW)).unwrap();
+
+        let mut after = Payouts::new(
+            MockBackend::default(),
+            MockResolver::default(),
+            SpendingLimits::default(),
+            before.audit_log().to_vec(),
+        );
+
+        assert_eq!(after.spent_today(NOW), 21);
+        assert!(block_on(after.pay(reward(1, "alice", 21), NOW)).is_ok());
+        assert!(after.backend.paid.lock().unwrap().is_empty());
+        assert_eq!(after.audit_log().len(), 2);
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use super::http::HttpClient;
+
+const USER_CHARS: &str = "abcdefghijklmnopqrstuvwxyz0123456789-_.+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LnurlError {
+    InvalidAddress(&'static str),
+    Http(String),
+    Remote(String),
+    NotPayRequest,
+}
+
+impl fmt::Display for LnurlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LnurlError::InvalidAddress(reason) => {
+                write!(f, "invalid lightning address: {}", reason)
+            }
+            LnurlError::Http(e) => write!(f, "couldn't reach the wallet: {}", e),
+            LnurlError::Remote(reason) => write!(f, "wallet said: {}", reason),
+            LnurlError::NotPayRequest => write!(f, "address can't receive payments"),
+        }
+    }
+}
+
+// LUD-16 `user@domain`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightningAddress {
+    pub user: String,
+    pub domain: String,
+}
+
+impl LightningAddress {
+    pub fn parse(address: &str) -> Result<Self, LnurlError> {
+        let address = address.trim().to_lowercase();
+        let Some((user, domain)) = address.split_once('@') else {
+            return Err(LnurlError::InvalidAddress("missing @"));
+        };
+
+        if user.is_empty() || !user.chars().all(|c| USER_CHARS.contains(c)) {
+            return Err(LnurlError::InvalidAddress("bad characters before @"));
+        }
+
+        // a port is allowed for local testing
+        let (host, port) = match domain.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (domain, None),
+        };
+        let labels: Vec<&str> = host.split('.').collect();
+        let valid_label = |label: &&str| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        };
+        let valid_host =
+            (labels.len() >= 2 || host == "localhost") && labels.iter().all(valid_label);
+        let valid_port = port.is_none_or(|port| port.parse::<u16>().is_ok());
+        if !valid_host || !valid_port {
+            return Err(LnurlError::InvalidAddress("bad domain"));
+        }
+
+        Ok(Self {
+            user: user.to_string(),
+            domain: domain.to_string(),
+        })
+    }
+
+    // an empty endpoint means the address' own domain, anything else (e.g. a local mock)
+    // serves the well-known path for every address
+    pub fn lnurlp_url(&self, endpoint: &str) -> String {
+        let base = if !endpoint.trim().is_empty() {
+            endpoint.trim().trim_end_matches('/').to_string()
+        } else if self.domain.ends_with(".onion") || self.domain.starts_with("localhost") {
+            format!("http://{}", self.domain)
+        } else {
+            format!("https://{}", self.domain)
+        };
+
+        format!("{}/.well-known/lnurlp/{}", base, self.user)
+    }
+}
+
+impl fmt::Display for LightningAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.user, self.domain)
+    }
+}
+
+// LUD-06 payRequest, amounts in millisats
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PayParams {
+    pub callback: String,
+    pub min_sendable: u64,
+    pub max_sendable: u64,
+    pub metadata: String,
+    pub tag: String,
+}
+
+impl PayParams {
+    pub fn min_sats(&self) -> u64 {
+        self.min_sendable.div_ceil(1000)
+    }
+
+    pub fn max_sats(&self) -> u64 {
+        self.max_sendable / 1000
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LnurlResponse {
+    Pay(PayParams),
+    Error { reason: String },
+}
+
+pub fn parse_pay_response(body: &str) -> Result<PayParams, LnurlError> {
+    match serde_json::from_str(body) {
+        Ok(LnurlResponse::Pay(params)) if params.tag == "payRequest" => Ok(params),
+        Ok(LnurlResponse::Pay(_)) => Err(LnurlError::NotPayRequest),
+        Ok(LnurlResponse::Error { reason }) => Err(LnurlError::Remote(reason)),
+        Err(e) => Err(LnurlError::Http(e.to_string())),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InvoiceResponse {
+    Invoice { pr: String },
+    Error { reason: String },
+}
+
+// second LUD-06 step: the callback url asking for a bolt11 invoice of `msats`
+pub fn invoice_url(params: &PayParams, msats: u64) -> Result<String, LnurlError> {
+    if msats < params.min_sendable || msats > params.max_sendable {
+        return Err(LnurlError::Remote(format!(
+            "amount must be between {} and {} sats",
+            params.min_sats(),
+            params.max_sats()
+        )));
+    }
+
+    let separator = if params.callback.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+
+    Ok(format!("{}{}amount={}", params.callback, separator, msats))
+}
+
+pub fn parse_invoice_response(body: &str) -> Result<String, LnurlError> {
+    match serde_json::from_str(body) {
+        Ok(InvoiceResponse::Invoice { pr }) => Ok(pr),
+        Ok(InvoiceResponse::Error { reason }) => Err(LnurlError::Remote(reason)),
+        Err(e) => Err(LnurlError::Http(e.to_string())),
+    }
+}
+
+pub async fn request_invoice<H: HttpClient + ?Sized>(
+    http: &H,
+    params: &PayParams,
+    msats: u64,
+) -> Result<String, LnurlError> {
+    let url = invoice_url(params, msats)?;
+    let body = http.get(&url, &[]).await.map_err(LnurlError::Http)?;
+
+    parse_invoice_response(&body)
+}
+
+pub async fn fetch_pay_params<H: HttpClient + ?Sized>(
+    http: &H,
+    address: &LightningAddress,
+    endpoint: &str,
+) -> Result<PayParams, LnurlError> {
+    let body = http
+        .get(&address.lnurlp_url(endpoint), &[])
+        .await
+        .map_err(LnurlError::Http)?;
+
+    parse_pay_response(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pay_params(min_sendable: u64, max_sendable: u64) -> PayParams {
+        PayParams {
+            callback: "https://example.com/callback".to_string(),
+            min_sendable,
+            max_sendable,
+            metadata: "[]".to_string(),
+            tag: "payRequest".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_valid_address() {
+        let address = LightningAddress::parse("  Satoshi@Example.com ").unwrap();
+        assert_eq!(address.user, "satoshi");
+        assert_eq!(address.domain, "example.com");
+        assert_eq!(
+            address.lnurlp_url(""),
+            "https://example.com/.well-known/lnurlp/satoshi"
+        );
+
+        let local = LightningAddress::parse("alice@localhost:8080").unwrap();
+        assert_eq!(
+            local.lnurlp_url(""),
+            "http://localhost:8080/.well-known/lnurlp/alice"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_addresses() {
+        let missing_at = LnurlError::InvalidAddress("missing @");
+        let bad_user = LnurlError::InvalidAddress("bad characters before @");
+        let bad_domain = LnurlError::InvalidAddress("bad domain");
+
+        assert_eq!(LightningAddress::parse("satoshi"), Err(missing_at));
+        assert_eq!(
+            LightningAddress::parse("@example.com"),
+            Err(bad_user.clone())
+        );
+        assert_eq!(
+            LightningAddress::parse("sa toshi@example.com"),
+            Err(bad_user)
+        );
+        for address in [
+            "satoshi@",
+            "satoshi@example",
+            "satoshi@example..com",
+            "satoshi@-example.com",
+            "satoshi@exa_mple.com",
+            "satoshi@example.com:99999",
+        ] {
+            assert_eq!(
+                LightningAddress::parse(address),
+                Err(bad_domain.clone()),
+                "{}",
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn parses_pay_request() {
+        let body = r#"{
+            "callback": "https://example.com/callback",
+            "minSendable": 1000,
+            "maxSendable": 100000000,
+            "metadata": "[[\"text/plain\",\"sats\"]]",
+            "tag": "payRequest"
+        }"#;
+        let params = parse_pay_response(body).unwrap();
+        assert_eq!(params.callback, "https://example.com/callback");
+        assert_eq!(params.min_sendable, 1000);
+        assert_eq!(params.max_sendable, 100_000_000);
+    }
+
+    #[test]
+    fn rejects_wrong_tag() {
+        let body = r#"{
+            "callback": "https://example.com/callback",
+            "minSendable": 1000,
+            "maxSendable": 1000,
+            "metadata": "[]",
+            "tag": "withdrawRequest"
+        }"#;
+        assert_eq!(parse_pay_response(body), Err(LnurlError::NotPayRequest));
+    }
+
+    #[test]
+    fn surfaces_remote_errors() {
+        let body = r#"{"status": "ERROR", "reason": "unknown user"}"#;
+        assert_eq!(
+            parse_pay_response(body),
+            Err(LnurlError::Remote("unknown user".to_string()))
+        );
+        assert!(matches!(
+            parse_pay_response("not json"),
+            Err(LnurlError::Http(_))
+        ));
+    }
+
+    #[test]
+    fn sendable_bounds_round_inwards() {
+        let params = pay_params(1500, 2_500_999);
+        assert_eq!(params.min_sats(), 2);
+        assert_eq!(params.max_sats(), 2500);
+    }
+
+    #[test]
+    fn invoice_amount_must_be_sendable() {
+        let params = pay_params(10_000, 20_000);
+        let expected = Err(LnurlError::Remote(
+            "amount must be between 10 and 20 sats".to_string(),
+        ));
+        assert_eq!(invoice_url(&params, 9_999), expected);
+        assert_eq!(invoice_url(&params, 20_001), expected);
+        assert_eq!(
+            invoice_url(&params, 10_000).unwrap(),
+            "https://example.com/callback?amount=10000"
+        );
+    }
+
+    #[test]
+    fn parses_invoice_response() {
+        assert_eq!(
+            parse_invoice_response(r#"{"pr": "lnbc1", "routes": []}"#),
+            Ok("lnbc1".to_string())
+        );
+        assert_eq!(
+            parse_invoice_response(r#"{"status": "ERROR", "reason": "too small"}"#),
+            Err(LnurlError::Remote("too small".to_string()))
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use super::{
+    backend::{Invoice, InvoiceBackend},
+    PayoutError,
+};
+
+// how long a player has to pay their entry invoice
+pub const ENTRY_EXPIRY_SECS: u64 = 10 * 60;
+
+// sats for each place; rounding leftovers go to first place so the whole pot is paid
+pub fn split_pot(pot_sats: u64, split: &[u8]) -> Vec<u64> {
+    let mut prizes: Vec<u64> = split
+        .iter()
+        .map(|percent| pot_sats * *percent as u64 / 100)
+        .collect();
+
+    let total_percent: u64 = split.iter().map(|percent| *percent as u64).sum();
+    if total_percent == 100 {
+        let leftover = pot_sats - prizes.iter().sum::<u64>();
+        if let Some(first) = prizes.first_mut() {
+            *first += leftover;
+        }
+    }
+
+    prizes
+}
+
+struct Entry {
+    tournament_id: u32,
+    player: Uuid,
+    invoice: Invoice,
+    expires_at: u64,
+    paid: bool,
+}
+
+// takes entry fees and tracks who has paid into which tournament
+pub struct EntryDesk<B: InvoiceBackend> {
+    backend: B,
+    entries: HashMap<String, Entry>,
+}
+
+impl<B: InvoiceBackend> EntryDesk<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub async fn register(
+        &mut self,
+        tournament_id: u32,
+        player: Uuid,
+        entry_sats: u64,
+        now: u64,
+    ) -> Result<Invoice, PayoutError> {
+        // asking twice hands back the same invoice until it expires, a paid one is kept for good
+        self.entries
+            .retain(|_, entry| entry.paid || entry.expires_at > now);
+        if let Some(entry) = self
+            .entries
+            .values()
+            .find(|entry| entry.tournament_id == tournament_id && entry.player == player)
+        {
+            return Ok(entry.invoice.clone());
+        }
+
+        let memo = format!("rain.run tournament {}", tournament_id);
+        let invoice = self
+            .backend
+            .create_invoice(entry_sats, &memo, ENTRY_EXPIRY_SECS)
+            .await?;
+        self.entries.insert(
+            invoice.payment_hash.clone(),
+            Entry {
+                tournament_id,
+                player,
+                invoice: invoice.clone(),
+                expires_at: now + ENTRY_EXPIRY_SECS,
+                paid: false,
+            },
+        );
+
+        Ok(invoice)
+    }
+
+    // checks unpaid invoices and returns newly confirmed (tournament, player) pairs,
+    // expired ones that never got paid are dropped
+    pub async fn confirm_payments(&mut self, now: u64) -> Result<Vec<(u32, Uuid)>, PayoutError> {
+        let mut confirmed = Vec::new();
+
+        for entry in self.entries.values_mut().filter(|entry| !entry.paid) {
+            if self.backend.is_paid(&entry.invoice.payment_hash).await? {
+                entry.paid = true;
+                confirmed.push((entry.tournament_id, entry.player));
+            }
+        }
+        self.entries
+            .retain(|_, entry| entry.paid || entry.expires_at > now);
+
+        Ok(confirmed)
+    }
+
+    pub fn is_registered(&self, tournament_id: u32, player: Uuid) -> bool {
+        self.entries.values().any(|entry| {
+            entry.paid && entry.tournament_id == tournament_id && entry.player == player
+        })
+    }
+
+    pub fn pot(&self, tournament_id: u32) -> u64 {
+        self.entries
+            .values()
+            .filter(|entry| entry.paid && entry.tournament_id == tournament_id)
+            .map(|entry| entry.invoice.sats)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::backend::MockInvoices;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn splits_whole_pot() {
+        assert_eq!(split_pot(1000, &[50, 30, 20]), [500, 300, 200]);
+        assert_eq!(split_pot(0, &[50, 30, 20]), [0, 0, 0]);
+        assert_eq!(split_pot(1000, &[100]), [1000]);
+    }
+
+    #[test]
+    fn rounding_leftovers_go_to_first_place() {
+        // 3.3 + 3.3 + 3.4 rounds down to 9, the odd sat goes to the winner
+        assert_eq!(split_pot(10, &[33, 33, 34]), [4, 3, 3]);
+        assert_eq!(split_pot(7, &[50, 30, 20]), [4, 2, 1]);
+        assert_eq!(split_pot(999, &[60, 25, 15]).iter().sum::<u64>(), 999);
+    }
+
+    #[test]
+    fn partial_split_keeps_remainder() {
+        // a split under 100% leaves the rest in the house instead of topping up first place
+        assert_eq!(split_pot(1000, &[50, 25]), [500, 250]);
+        assert!(split_pot(1000, &[]).is_empty());
+    }
+
+    #[test]
+    fn unpaid_entry_is_not_registered() {
+        let player = Uuid::from_u128(1);
+        let mut desk = EntryDesk::new(MockInvoices::default());
+
+        let invoice = block_on(desk.register(7, player, 100, NOW)).unwrap();
+
+        assert_eq!(invoice.sats, 100);
+        assert!(block_on(desk.confirm_payments(NOW)).unwrap().is_empty());
+        assert!(!desk.is_registered(7, player));
+        assert_eq!(desk.pot(7), 0);
+
+        desk.backend.settle(&invoice.payment_hash);
+        assert_eq!(block_on(desk.confirm_payments(NOW)).unwrap(), [(7, player)]);
+        assert!(desk.is_registered(7, player));
+        assert!(!desk.is_registered(8, player));
+        assert_eq!(desk.pot(7), 100);
+    }
+
+    #[test]
+    fn paying_twice_registers_once() {
+        let player = Uuid::from_u128(1);
+        let mut desk = EntryDesk::new(MockInvoices::default());
+
+        let first = block_on(desk.register(7, player, 100, NOW)).unwrap();
+        let again = block_on(desk.register(7, player, 100, NOW + 1)).unwrap();
+        assert_eq!(first, again);
+
+        desk.backend.settle(&first.payment_hash);
+        desk.backend.settle(&first.payment_hash);
+        assert_eq!(block_on(desk.confirm_payments(NOW)).unwrap(), [(7, player)]);
+        assert!(block_on(desk.confirm_payments(NOW)).unwrap().is_empty());
+
+        // a paid entry never gets a second invoice, even past the expiry
+        let later = block_on(desk.register(7, player, 100, NOW + ENTRY_EXPIRY_SECS * 2)).unwrap();
+        assert_eq!(later, first);
+        assert_eq!(desk.backend.issued.lock().unwrap().len(), 1);
+        assert_eq!(desk.pot(7), 100);
+    }
+
+    #[test]
+    fn expired_invoice_is_replaced() {
+        let player = Uuid::from_u128(1);
+        let mut desk = EntryDesk::new(MockInvoices::default());
+
+        let expired = block_on(desk.register(7, player, 100, NOW)).unwrap();
+        let after_expiry = NOW + ENTRY_EXPIRY_SECS;
+        assert!(block_on(desk.confirm_payments(after_expiry))
+            .unwrap()
+            .is_empty());
+
+        // settling the dropped invoice no longer counts
+        desk.backend.settle(&expired.payment_hash);
+        assert!(block_on(desk.confirm_payments(after_expiry))
+            .unwrap()
+            .is_empty());
+        assert!(!desk.is_registered(7, player));
+
+        let fresh = block_on(desk.register(7, player, 100, after_expiry)).unwrap();
+        assert_ne!(fresh.payment_hash, expired.payment_hash);
+        desk.backend.settle(&fresh.payment_hash);
+        assert_eq!(
+            block_on(desk.confirm_payments(after_expiry)).unwrap(),
+            [(7, player)]
+        );
+        assert_eq!(desk.pot(7), 100);
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::{audio::Volume, prelude::*};
+
+use bevy_egui::EguiContexts;
+use serde::{Deserialize, Serialize};
+
+use crate::game_util::{events::SoundEffect, resources::Objects, storage};
+
+const RAIN_VOLUME: f32 = 0.6;
+// this many drops on screen plays the rain loop at full volume
+const RAIN_FULL: f32 = 40.0;
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AudioSettings {
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl AudioSettings {
+    pub fn new() -> Self {
+        storage::load("audio_settings").unwrap_or(Self {
+            volume: 0.5,
+            muted: false,
+        })
+    }
+
+    pub fn effective_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+}
+
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AudioSettings::new())
+            .add_event::<SoundEffect>()
+            .add_systems(Startup, load_sounds)
+            .add_systems(
+                Update,
+                (
+                    ui_click_sounds,
+                    play_sounds,
+                    rain_ambience,
+                    save_audio_settings,
+                ),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct Sounds {
+    pickup: Handle<AudioSource>,
+    hit: Handle<AudioSource>,
+    complete: Handle<AudioSource>,
+    click: Handle<AudioSource>,
+    key: Handle<AudioSource>,
+}
+
+#[derive(Component)]
+pub struct RainAmbience;
+
+fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Sounds {
+        pickup: asset_server.load("sounds/pickup.wav"),
+        hit: asset_server.load("sounds/hit.wav"),
+        complete: asset_server.load("sounds/complete.wav"),
+        click: asset_server.load("sounds/click.wav"),
+        key: asset_server.load("sounds/key.wav"),
+    });
+
+    commands.spawn((
+        AudioBundle {
+            source: asset_server.load("sounds/rain_loop.wav"),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new_relative(0.0)),
+        },
+        RainAmbience,
+    ));
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    mut sound_events: EventReader<SoundEffect>,
+    sounds: Res<Sounds>,
+    audio_settings: Res<AudioSettings>,
+) {
+    let volume = audio_settings.effective_volume();
+
+    for effect in sound_events.iter() {
+        if volume <= 0.0 {
+            continue;
+        }
+
+        let source = match effect {
+            SoundEffect::Pickup => sounds.pickup.clone(),
+            SoundEffect::Hit => sounds.hit.clone(),
+            SoundEffect::Complete => sounds.complete.clone(),
+            SoundEffect::Click => sounds.click.clone(),
+            SoundEffect::Key => sounds.key.clone(),
+        };
+
+        commands.spawn(AudioBundle {
+            source,
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new_relative(volume)),
+        });
+    }
+}
+
+fn ui_click_sounds(mut contexts: EguiContexts, mut sound: EventWriter<SoundEffect>) {
+    let ctx = contexts.ctx_mut();
+
+    if ctx.input(|i| i.pointer.any_click()) && ctx.is_pointer_over_area() {
+        sound.send(SoundEffect::Click);
+    }
+}
+
+fn rain_ambience(
+    objects: Res<Objects>,
+    audio_settings: Res<AudioSettings>,
+    query_sink: Query<&AudioSink, With<RainAmbience>>,
+    camera_query: Query<(&GlobalTransform, &OrthographicProjection)>,
+    windows: Query<&Window>,
+) {
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let Some(window) = windows.iter().next() else {
+        return;
+    };
+
+    let center = camera_transform.translation().truncate();
+    let half_view = Vec2::new(window.width(), window.height()) * projection.scale / 2.0;
+
+    let on_screen = objects
+        .rain_pos
+        .iter()
+        .filter(|rain| {
+            let offset = (rain.pos.truncate() - center).abs();
+            offset.x <= half_view.x && offset.y <= half_view.y
+        })
+        .count();
+
+    let intensity = (on_screen as f32 / RAIN_FULL).min(1.0);
+
+    for sink in query_sink.iter() {
+        sink.set_volume(audio_settings.effective_volume() * RAIN_VOLUME * intensity);
+    }
+}
+
+fn save_audio_settings(audio_settings: Res<AudioSettings>) {
+    if audio_settings.is_changed() && !audio_settings.is_added() {
+        storage::save("audio_settings", &*audio_settings);
+    }
+}
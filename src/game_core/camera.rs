@@ -0,0 +1,155 @@
+use bevy::{input::mouse::MouseWheel, prelude::*};
+
+use bevy_egui::EguiContexts;
+
+use crate::{game_util::events::CameraShake, GameStage};
+
+use super::{
+    objects::{X_BOUNDS, Y_BOUNDS},
+    player::{Enemy, Player},
+    spectate::Spectate,
+};
+
+const ZOOM_LEVELS: [f32; 4] = [0.75, 1.0, 1.5, 2.0];
+const MAX_SHAKE: f32 = 12.0;
+const SHAKE_DECAY: f32 = 1.5;
+
+#[derive(Component)]
+pub struct CameraController {
+    pub smoothing: f32,
+    pub look_ahead: f32,
+    pub zoom: usize,
+    pub trauma: f32,
+    pub focus: Vec2,
+}
+
+impl CameraController {
+    pub fn new() -> Self {
+        Self {
+            smoothing: 8.0,
+            look_ahead: 60.0,
+            zoom: 1,
+            trauma: 0.0,
+            focus: Vec2::ZERO,
+        }
+    }
+}
+
+pub fn spawn_camera(mut commands: Commands) {
+    commands
+        .spawn(Camera2dBundle {
+            transform: Transform::from_translation(Vec3::new(0., 0., 10.0)),
+            projection: OrthographicProjection {
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(CameraController::new());
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn camera_follow(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mut scroll: EventReader<MouseWheel>,
+    mut shakes: EventReader<CameraShake>,
+    spectate: Res<Spectate>,
+    game_stage: Res<State<GameStage>>,
+    query_player: Query<(&Transform, &Player), Without<CameraController>>,
+    query_enemy: Query<(&Transform, &Enemy), Without<CameraController>>,
+    mut query_camera: Query<(
+        &mut Transform,
+        &mut OrthographicProjection,
+        &mut CameraController,
+    )>,
+    windows: Query<&Window>,
+) {
+    let dt = time.delta_seconds();
+    let wants_pointer = contexts.ctx_mut().wants_pointer_input();
+
+    let mut zoom_step = 0;
+    if keys.just_pressed(KeyCode::Minus) {
+        zoom_step += 1;
+    }
+    if keys.just_pressed(KeyCode::Equals) {
+        zoom_step -= 1;
+    }
+    for wheel in scroll.iter() {
+        if !wants_pointer {
+            zoom_step -= wheel.y.signum() as i32;
+        }
+    }
+
+    let spectating = *game_stage.get() == GameStage::Spectating;
+
+    let (focus, look_ahead) = if spectating {
+        let target = spectate
+            .target
+            .filter(|_| !spectate.overview)
+            .and_then(|target| query_enemy.iter().find(|(_, enemy)| enemy.id == target))
+            .map(|(t, _)| t.translation.truncate());
+        (target.unwrap_or(Vec2::ZERO), Vec2::ZERO)
+    } else if let Some((t, player)) = query_player.iter().next() {
+        let pos = t.translation.truncate();
+        let direction = (player.target - pos).normalize_or_zero();
+        let look_ahead = if *game_stage.get() == GameStage::InGame {
+            direction
+        } else {
+            Vec2::ZERO
+        };
+        (pos, look_ahead)
+    } else {
+        (Vec2::ZERO, Vec2::ZERO)
+    };
+
+    for (mut camera_t, mut projection, mut controller) in query_camera.iter_mut() {
+        for shake in shakes.iter() {
+            controller.trauma = (controller.trauma + shake.0).min(1.0);
+        }
+
+        controller.zoom =
+            (controller.zoom as i32 + zoom_step).clamp(0, ZOOM_LEVELS.len() as i32 - 1) as usize;
+
+        let window_size = windows
+            .iter()
+            .next()
+            .map(|window| Vec2::new(window.width(), window.height()))
+            .unwrap_or(Vec2::ONE);
+
+        let scale = if spectating && spectate.overview {
+            (X_BOUNDS * 2.0 / window_size.x).max(Y_BOUNDS * 2.0 / window_size.y)
+        } else {
+            ZOOM_LEVELS[controller.zoom]
+        };
+        projection.scale = scale;
+
+        let goal = focus + look_ahead * controller.look_ahead;
+        let blend = 1.0 - (-controller.smoothing * dt).exp();
+        controller.focus = controller.focus.lerp(goal, blend);
+
+        // keep the view inside the field, centre it when the view is bigger than the field
+        let half_view = window_size * scale / 2.0;
+        let clamp_axis = |value: f32, bounds: f32, half: f32| {
+            if half >= bounds {
+                0.0
+            } else {
+                value.clamp(-bounds + half, bounds - half)
+            }
+        };
+        let mut pos = Vec2::new(
+            clamp_axis(controller.focus.x, X_BOUNDS, half_view.x),
+            clamp_axis(controller.focus.y, Y_BOUNDS, half_view.y),
+        );
+
+        if controller.trauma > 0.0 {
+            let shake = controller.trauma * controller.trauma * MAX_SHAKE;
+            let t = time.elapsed_seconds();
+            pos += Vec2::new((t * 37.0).sin(), (t * 53.0).cos()) * shake;
+            controller.trauma = (controller.trauma - SHAKE_DECAY * dt).max(0.0);
+        }
+
+        camera_t.translation.x = pos.x;
+        camera_t.translation.y = pos.y;
+    }
+}
@@ -0,0 +1,253 @@
+use bevy::{prelude::*, utils::Instant};
+
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContexts,
+};
+use satrunner_payout::SECS_PER_DAY;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    game_util::{events::ChallengeUpdate, resources::NetworkStuff},
+    network::messages::{ChallengeMessage, ChallengeSchedule, ChallengeWeek, ClientMessage},
+    GameStage,
+};
+
+use super::{
+    modes::{format_duration, SelectedMode},
+    settings::SettingsMenu,
+};
+
+#[derive(Resource)]
+pub struct Challenge {
+    pub schedule: Option<ChallengeSchedule>,
+    pub received: Instant,
+    pub archive: Vec<ChallengeWeek>,
+}
+
+impl Challenge {
+    pub fn new() -> Self {
+        Self {
+            schedule: None,
+            received: Instant::now(),
+            archive: Vec::new(),
+        }
+    }
+
+    // server clock in unix seconds, ticked forward locally so the countdown doesn't trust the device clock
+    pub fn server_now(&self) -> Option<u64> {
+        self.schedule
+            .as_ref()
+            .map(|schedule| schedule.server_time + self.received.elapsed().as_secs())
+    }
+
+    // days since the unix epoch, in server time
+    pub fn server_day(&self) -> Option<u64> {
+        self.server_now().map(|now| now / SECS_PER_DAY)
+    }
+
+    // (seconds until start, seconds until end)
+    pub fn countdown(&self) -> Option<(i64, i64)> {
+        let schedule = self.schedule.as_ref()?;
+        let now = self.server_now()? as i64;
+        Some((
+            schedule.starts_at as i64 - now,
+            schedule.ends_at as i64 - now,
+        ))
+    }
+}
+
+pub struct ChallengePlugin;
+
+impl Plugin for ChallengePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Challenge::new())
+            .add_event::<ChallengeUpdate>()
+            .add_systems(Update, apply_challenge_updates)
+            .add_systems(OnEnter(GameStage::Menu), request_challenge)
+            .add_systems(Update, challenge_panel.run_if(in_state(GameStage::Menu)));
+    }
+}
+
+// hex sha256 of the seed's little endian bytes, what the server commits to before the week starts
+pub fn seed_commitment(seed: u64) -> String {
+    Sha256::digest(seed.to_le_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// the daily seed only depends on the date, so everyone plays the same rain that day
+pub fn daily_seed(day: u64) -> u64 {
+    let hash = Sha256::digest(format!("rain.run daily {}", day).as_bytes());
+    u64::from_le_bytes(hash[..8].try_into().unwrap())
+}
+
+pub fn seed_matches(week: &ChallengeWeek) -> bool {
+    seed_commitment(week.seed).eq_ignore_ascii_case(&week.seed_commitment)
+}
+
+fn request_challenge(mut network_stuff: ResMut<NetworkStuff>) {
+    // startup leaves no socket when the server couldn't be reached
+    if network_stuff.write.is_some() {
+        network_stuff.send(ClientMessage::GetChallenge);
+    }
+}
+
+fn apply_challenge_updates(
+    mut challenge: ResMut<Challenge>,
+    mut updates: EventReader<ChallengeUpdate>,
+) {
+    for update in updates.iter() {
+        match &update.0 {
+            ChallengeMessage::Schedule(schedule) => {
+                challenge.schedule = Some(schedule.clone());
+                challenge.received = Instant::now();
+            }
+            ChallengeMessage::Archive(archive) => {
+                challenge.archive = archive.clone();
+                challenge
+                    .archive
+                    .sort_unstable_by(|a, b| b.week.cmp(&a.week));
+            }
+            // handled by the daily plugin
+            ChallengeMessage::DailyResult(_) | ChallengeMessage::DailyRejected(_) => {}
+        }
+    }
+}
+
+fn past_week(ui: &mut egui::Ui, week: &ChallengeWeek) {
+    ui.label(RichText::new(format!("Week {} - {}", week.week, week.mode.title())).strong());
+    for (place, (name, score)) in week.winners.iter().enumerate() {
+        ui.label(format!(
+            "{}: {} ({})",
+            place + 1,
+            name,
+            week.mode.format_high_score(*score)
+        ));
+    }
+    if week.winners.is_empty() {
+        ui.label("Nobody finished");
+    }
+
+    let (mark, color) = if seed_matches(week) {
+        ("✔ matches commitment", Color32::GREEN)
+    } else {
+        ("✘ doesn't match commitment", Color32::LIGHT_RED)
+    };
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(format!("seed {}", week.seed)).monospace())
+            .on_hover_text(&week.seed_commitment);
+        ui.label(RichText::new(mark).color(color));
+    });
+}
+
+fn challenge_panel(
+    mut contexts: EguiContexts,
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut selected_mode: ResMut<SelectedMode>,
+    challenge: Res<Challenge>,
+    settings_menu: Res<SettingsMenu>,
+    mut refreshed: Local<Option<u32>>,
+) {
+    if settings_menu.open {
+        return;
+    }
+    let (Some(schedule), Some((starts_in, ends_in))) =
+        (challenge.schedule.as_ref(), challenge.countdown())
+    else {
+        return;
+    };
+
+    // once the week rolls over ask for the next schedule and the revealed seed, once per week
+    if ends_in <= 0 && *refreshed != Some(schedule.week) && network_stuff.write.is_some() {
+        *refreshed = Some(schedule.week);
+        network_stuff.send(ClientMessage::GetChallenge);
+    }
+
+    egui::Window::new("🏆 Weekly Challenge")
+        .resizable(false)
+        .collapsible(true)
+        .anchor(egui::Align2::RIGHT_TOP, egui::Vec2::new(-10.0, 10.0))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("Week {}: {}", schedule.week, schedule.mode.title()));
+            ui.label(schedule.mode.description());
+
+            let countdown = if starts_in > 0 {
+                format!("starts in {}", format_duration(starts_in))
+            } else if ends_in > 0 {
+                format!("ends in {}", format_duration(ends_in))
+            } else {
+                "the next week is starting…".to_string()
+            };
+            ui.label(RichText::new(countdown).color(Color32::YELLOW));
+
+            let short: String = schedule.seed_commitment.chars().take(16).collect();
+            ui.label(RichText::new(format!("seed commitment {}…", short)).monospace())
+                .on_hover_text(&schedule.seed_commitment);
+
+            if selected_mode.mode != schedule.mode && ui.button("Select this mode").clicked() {
+                selected_mode.mode = schedule.mode;
+            }
+
+            if !challenge.archive.is_empty() {
+                ui.separator();
+                ui.collapsing("Past winners", |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for week in challenge.archive.iter() {
+                                past_week(ui, week);
+                                ui.add_space(6.0);
+                            }
+                        });
+                });
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::messages::GameMode;
+
+    use super::*;
+
+    fn week(seed: u64, seed_commitment: &str) -> ChallengeWeek {
+        ChallengeWeek {
+            week: 1,
+            mode: GameMode::TimeAttack,
+            starts_at: 0,
+            ends_at: SECS_PER_DAY * 7,
+            seed,
+            seed_commitment: seed_commitment.to_string(),
+            winners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn commits_to_known_seeds() {
+        assert_eq!(
+            seed_commitment(0),
+            "af5570f5a1810b7af78caf4bc70a660f0df51e42baf91d4de5b2328de0e83dfc"
+        );
+        assert_eq!(
+            seed_commitment(42),
+            "ed049108bc18f2c64369e8d0ea42850bdd1a7d1dd340cfde716315579702a76c"
+        );
+    }
+
+    #[test]
+    fn checks_revealed_seed_against_commitment() {
+        let commitment = "ED049108BC18F2C64369E8D0EA42850BDD1A7D1DD340CFDE716315579702A76C";
+        assert!(seed_matches(&week(42, commitment)));
+        assert!(!seed_matches(&week(43, commitment)));
+    }
+
+    #[test]
+    fn daily_seed_is_fixed_per_day() {
+        assert_eq!(daily_seed(0), 7645003904709274071);
+        assert_eq!(daily_seed(19000), 15525374962083939592);
+        assert_eq!(daily_seed(19000), daily_seed(19000));
+        assert_ne!(daily_seed(19000), daily_seed(19001));
+    }
+}
@@ -0,0 +1,364 @@
+use bevy::{prelude::*, utils::Instant};
+
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContexts,
+};
+use satrunner_payout::SECS_PER_DAY;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_util::{
+        events::ChallengeUpdate,
+        resources::{FixedRun, NetworkStuff, Objects, PendingRun, PlayerName},
+        storage,
+    },
+    network::messages::{ChallengeMessage, ClientMessage, GameMode},
+    GameStage, KeyboardState,
+};
+
+use super::{
+    challenge::{daily_seed, Challenge},
+    modes::{format_duration, format_secs, SelectedMode, BOLT_TARGET},
+    player::Player,
+    settings::SettingsMenu,
+};
+
+// the daily is always a race to the bolt target
+const DAILY_MODE: GameMode = GameMode::TimeAttack;
+const BOLTS_PER_ROW: usize = 7;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DailyRecord {
+    pub day: u64,
+    pub score: usize,
+    pub secs: u64,
+    pub won: bool,
+    // place and number of players, filled in once the server ranks the run
+    pub rank: Option<(u32, u32)>,
+}
+
+#[derive(Resource)]
+pub struct Daily {
+    // the day being played, set while the official attempt is running
+    pub active: Option<u64>,
+    pub record: Option<DailyRecord>,
+    pub show_card: bool,
+    // the server turned down the day's attempt, it was already played elsewhere
+    pub rejected: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl Daily {
+    pub fn new() -> Self {
+        Self {
+            active: None,
+            record: storage::load("daily"),
+            show_card: false,
+            rejected: None,
+            error: None,
+        }
+    }
+
+    pub fn played(&self, day: u64) -> bool {
+        self.record.as_ref().is_some_and(|record| record.day == day) || self.rejected == Some(day)
+    }
+}
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Daily::new())
+            .add_systems(Update, apply_daily_updates)
+            .add_systems(Update, daily_panel.run_if(in_state(GameStage::Menu)))
+            .add_systems(Update, check_daily_seed.run_if(in_state(GameStage::InGame)))
+            .add_systems(OnEnter(GameStage::GameOver), record_daily)
+            .add_systems(Update, daily_card.run_if(in_state(GameStage::GameOver)))
+            .add_systems(OnExit(GameStage::GameOver), hide_daily_card);
+    }
+}
+
+// text summary in the style of daily puzzle games, bolts as a grid so it reads at a glance
+pub fn result_card(record: &DailyRecord) -> String {
+    let bolts: Vec<String> = (0..BOLT_TARGET)
+        .collect::<Vec<_>>()
+        .chunks(BOLTS_PER_ROW)
+        .map(|row| {
+            row.iter()
+                .map(|i| if *i < record.score { "⚡" } else { "☁" })
+                .collect()
+        })
+        .collect();
+
+    let time = if record.won {
+        format!("⏱ {}", format_secs(record.secs))
+    } else {
+        format!("☔ caught in the rain at {}", format_secs(record.secs))
+    };
+
+    let mut card = format!(
+        "☔ rain.run daily #{}\n{}\n{}/{} ⚡ {}",
+        record.day,
+        bolts.join("\n"),
+        record.score.min(BOLT_TARGET),
+        BOLT_TARGET,
+        time
+    );
+    if let Some((rank, players)) = record.rank {
+        card.push_str(&format!("\n🏅 #{} of {}", rank, players));
+    }
+    card
+}
+
+fn apply_daily_updates(mut daily: ResMut<Daily>, mut updates: EventReader<ChallengeUpdate>) {
+    for update in updates.iter() {
+        match &update.0 {
+            ChallengeMessage::DailyResult(result) => {
+                if let Some(record) = daily
+                    .record
+                    .as_mut()
+                    .filter(|record| record.day == result.day)
+                {
+                    record.rank = Some((result.rank, result.players));
+                    storage::save("daily", record);
+                }
+            }
+            // the run in progress no longer counts, so it isn't recorded as the official one
+            ChallengeMessage::DailyRejected(day) => {
+                if daily.active == Some(*day) {
+                    daily.active = None;
+                }
+                daily.rejected = Some(*day);
+            }
+            _ => {}
+        }
+    }
+}
+
+// an attempt on any other world wouldn't be the same rain for everyone, so it's dropped
+// and the player can start it again from the menu
+fn check_daily_seed(
+    mut daily: ResMut<Daily>,
+    objects: Res<Objects>,
+    pending_run: Res<PendingRun>,
+    mut next_state: ResMut<NextState<GameStage>>,
+    mut keyboard_state: ResMut<NextState<KeyboardState>>,
+) {
+    // until the new world arrives the seed is still the last game's
+    if pending_run.0.is_some() {
+        return;
+    }
+    let Some(day) = daily.active else {
+        return;
+    };
+    let Some(seed) = objects.rng_seed else {
+        return;
+    };
+
+    if seed != daily_seed(day) {
+        warn!(
+            "daily #{} started on seed {}, not the day's seed",
+            day, seed
+        );
+        daily.active = None;
+        daily.error = Some("The server started a different world, try the daily again".to_string());
+        keyboard_state.set(KeyboardState::On);
+        next_state.set(GameStage::Menu);
+    }
+}
+
+// the first finished run of the day is the official one, later runs are regular games
+fn record_daily(mut daily: ResMut<Daily>, query_player: Query<&Player>) {
+    let Some(day) = daily.active.take() else {
+        return;
+    };
+    let Some(player) = query_player.iter().next() else {
+        return;
+    };
+
+    let record = DailyRecord {
+        day,
+        score: player.score,
+        secs: player.death_time.unwrap_or(0),
+        won: DAILY_MODE.is_won(player.score, player.won),
+        rank: None,
+    };
+    storage::save("daily", &record);
+    daily.record = Some(record);
+    daily.show_card = true;
+}
+
+fn card_ui(ui: &mut egui::Ui, record: &DailyRecord) {
+    let card = result_card(record);
+    ui.label(RichText::new(&card).monospace());
+    if record.rank.is_none() {
+        ui.label(RichText::new("waiting for your rank…").color(Color32::GRAY));
+    }
+    if ui.button("📋 Copy result").clicked() {
+        ui.output_mut(|o| o.copied_text = card);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn daily_panel(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameStage>>,
+    mut keyboard_state: ResMut<NextState<KeyboardState>>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut daily: ResMut<Daily>,
+    mut selected_mode: ResMut<SelectedMode>,
+    mut player_name: ResMut<PlayerName>,
+    mut query_player: Query<&mut Player>,
+    challenge: Res<Challenge>,
+    settings_menu: Res<SettingsMenu>,
+    mut pending_run: ResMut<PendingRun>,
+) {
+    if settings_menu.open {
+        return;
+    }
+    // the day comes from the server clock, without it there's no fair "today"
+    let (Some(day), Some(now)) = (challenge.server_day(), challenge.server_now()) else {
+        return;
+    };
+
+    let mut play = false;
+
+    egui::Window::new("☀ Daily Challenge")
+        .resizable(false)
+        .collapsible(true)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::Vec2::new(-10.0, -10.0))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("#{}: {}", day, DAILY_MODE.description()));
+            if let Some(error) = &daily.error {
+                ui.colored_label(Color32::RED, error);
+            }
+
+            match daily.record.as_ref().filter(|record| record.day == day) {
+                Some(record) => {
+                    card_ui(ui, record);
+                    let next = (day + 1) * SECS_PER_DAY - now;
+                    ui.label(format!("Next daily in {}", format_duration(next as i64)));
+                }
+                None if daily.rejected == Some(day) => {
+                    ui.label("Today's daily was already played with this profile.");
+                    let next = (day + 1) * SECS_PER_DAY - now;
+                    ui.label(format!("Next daily in {}", format_duration(next as i64)));
+                }
+                None => {
+                    ui.label("One official attempt, same rain for everyone.");
+                    if ui
+                        .add_enabled(
+                            !player_name.name.is_empty(),
+                            egui::Button::new("Play today's daily"),
+                        )
+                        .clicked()
+                    {
+                        play = true;
+                    }
+                    if player_name.name.is_empty() {
+                        ui.label("Enter a name first");
+                    }
+                }
+            }
+        });
+
+    if play && !daily.played(day) {
+        daily.active = Some(day);
+        daily.error = None;
+        // the server answers with the day's world, which must not bounce us to the menu
+        pending_run.0 = Some(FixedRun::Daily(day));
+        selected_mode.mode = DAILY_MODE;
+        keyboard_state.set(KeyboardState::Off);
+        player_name.submitted = true;
+        network_stuff.send(ClientMessage::SelectMode(DAILY_MODE));
+        network_stuff.send(ClientMessage::PlayDaily(day));
+        network_stuff.send(ClientMessage::PlayerName(player_name.name.clone()));
+
+        for mut player in query_player.iter_mut() {
+            player.spawn_time = Some(Instant::now());
+            player.name = player_name.name.clone();
+        }
+
+        next_state.set(GameStage::InGame);
+    }
+}
+
+fn daily_card(mut contexts: EguiContexts, daily: Res<Daily>) {
+    // only after the official run, not on every game over that day
+    let Some(record) = daily.record.as_ref().filter(|_| daily.show_card) else {
+        return;
+    };
+
+    egui::Window::new("☀ Daily result")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::Vec2::new(-10.0, -10.0))
+        .show(contexts.ctx_mut(), |ui| card_ui(ui, record));
+}
+
+fn hide_daily_card(mut daily: ResMut<Daily>) {
+    daily.show_card = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn won_card_shows_time_and_rank() {
+        let record = DailyRecord {
+            day: 19000,
+            score: 21,
+            secs: 75,
+            won: true,
+            rank: Some((3, 40)),
+        };
+
+        assert_eq!(
+            result_card(&record),
+            "☔ rain.run daily #19000\n\
+             ⚡⚡⚡⚡⚡⚡⚡\n\
+             ⚡⚡⚡⚡⚡⚡⚡\n\
+             ⚡⚡⚡⚡⚡⚡⚡\n\
+             21/21 ⚡ ⏱ 01:15\n\
+             🏅 #3 of 40"
+        );
+    }
+
+    #[test]
+    fn lost_card_shows_missed_bolts() {
+        let record = DailyRecord {
+            day: 19000,
+            score: 9,
+            secs: 42,
+            won: false,
+            rank: None,
+        };
+
+        assert_eq!(
+            result_card(&record),
+            "☔ rain.run daily #19000\n\
+             ⚡⚡⚡⚡⚡⚡⚡\n\
+             ⚡⚡☁☁☁☁☁\n\
+             ☁☁☁☁☁☁☁\n\
+             9/21 ⚡ ☔ caught in the rain at 00:42"
+        );
+    }
+
+    #[test]
+    fn official_attempt_counts_once() {
+        let mut daily = Daily {
+            active: None,
+            record: None,
+            show_card: false,
+            rejected: None,
+            error: None,
+        };
+        assert!(!daily.played(19000));
+
+        daily.rejected = Some(19000);
+        assert!(daily.played(19000));
+        assert!(!daily.played(19001));
+    }
+}
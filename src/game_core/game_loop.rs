@@ -6,55 +6,57 @@ use crate::game_util::{
 };
 use bevy::{prelude::*, utils::Instant};
 
-use super::player::{Enemy, Player};
+use super::{
+    modes::SelectedMode,
+    player::{Enemy, Player},
+    teams::team_color,
+};
 
 pub fn player_loop(
     mut query_player: Query<(&mut Transform, &mut Player, &mut Sprite)>,
     mut query_text: Query<&mut Text, With<NamePlatesLocal>>,
     client_tick: Res<ClientTick>,
+    selected_mode: Res<SelectedMode>,
 ) {
     for (mut t, mut player, mut sprite) in query_player.iter_mut() {
         t.translation.z = 1.0;
-        sprite.color = default();
+        sprite.color = player.team.map(team_color).unwrap_or_default();
 
         let duration = Instant::now() - player.spawn_time.unwrap();
         let seconds = duration.as_secs();
-        let minutes = seconds / 60;
 
         for mut text in query_text.iter_mut() {
-            text.sections[0].value = format!(
-                "{:02}/21\n({:02}:{:02})",
-                player.score,
-                minutes % 60,
-                seconds % 60,
-            );
+            text.sections[0].value = selected_mode.mode.hud_text(player.score, seconds);
+            text.sections[0].style.color = player.team.map(team_color).unwrap_or(Color::LIME_GREEN);
         }
 
-        player.apply_input(&mut t, &client_tick);
+        // the server ends the run, hold still until the game over arrives
+        if !selected_mode.mode.is_over(player.score, seconds) {
+            player.apply_input(&mut t, &client_tick);
+        }
     }
 }
 
 pub fn enemy_loop(
-    mut query_enemy: Query<(&mut Transform, &mut Enemy)>,
+    mut query_enemy: Query<(&mut Transform, &mut Enemy, &mut Sprite)>,
     mut query_text: Query<(&mut Text, &NamePlates)>,
     client_tick: Res<ClientTick>,
 ) {
-    for (mut t, mut enemy) in query_enemy.iter_mut() {
+    for (mut t, mut enemy, mut sprite) in query_enemy.iter_mut() {
+        sprite.color = enemy.team.map(team_color).unwrap_or_default();
+
         enemy.spawn_time.tick(Duration::from_millis(100));
         let duration = &enemy.spawn_time;
         let seconds = duration.elapsed_secs() as u64;
 
-        let minutes = seconds / 60;
-
         for (mut text, plates) in query_text.iter_mut() {
             if plates.id == enemy.id {
                 text.sections[0].value = format!(
-                    "{:02}/21\n({:02}:{:02})\n{}",
-                    enemy.score,
-                    minutes % 60,
-                    seconds % 60,
+                    "{}\n{}",
+                    enemy.mode.hud_text(enemy.score, seconds),
                     enemy.name
                 );
+                text.sections[0].style.color = enemy.team.map(team_color).unwrap_or(Color::WHITE);
             }
         }
 
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+
+use bevy_egui::{egui, EguiInput};
+
+use crate::{
+    game_util::resources::{ClientTick, NetworkStuff},
+    GameStage,
+};
+
+use super::{
+    input::{steer, DirectionThrottle},
+    player::Player,
+    settings::SettingsMenu,
+};
+
+// stick travel ignored around the centre
+const DEAD_ZONE: f32 = 0.2;
+const NAV_REPEAT: f32 = 0.25;
+
+fn stick_direction(gamepad: Gamepad, axes: &Axis<GamepadAxis>) -> Vec2 {
+    let stick = Vec2::new(
+        axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+            .unwrap_or(0.0),
+        axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+            .unwrap_or(0.0),
+    );
+
+    // radial rather than per-axis so diagonals near the edge of the zone aren't snapped
+    if stick.length() < DEAD_ZONE {
+        Vec2::ZERO
+    } else {
+        stick
+    }
+}
+
+fn dpad_direction(gamepad: Gamepad, buttons: &Input<GamepadButton>) -> Vec2 {
+    [
+        (GamepadButtonType::DPadUp, Vec2::Y),
+        (GamepadButtonType::DPadDown, Vec2::NEG_Y),
+        (GamepadButtonType::DPadLeft, Vec2::NEG_X),
+        (GamepadButtonType::DPadRight, Vec2::X),
+    ]
+    .iter()
+    .filter(|(button, _)| buttons.pressed(GamepadButton::new(gamepad, *button)))
+    .map(|(_, direction)| *direction)
+    .sum()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn gamepad_input(
+    mut query: Query<(&Transform, &mut Player)>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    mut outgoing: ResMut<NetworkStuff>,
+    client_tick: Res<ClientTick>,
+    settings_menu: Res<SettingsMenu>,
+    time: Res<Time>,
+    mut throttle: Local<DirectionThrottle>,
+) {
+    let Some(gamepad) = gamepads.iter().next() else {
+        return;
+    };
+    if client_tick.pause != 0 {
+        return;
+    }
+
+    let mut direction = stick_direction(gamepad, &axes);
+    if direction == Vec2::ZERO {
+        direction = dpad_direction(gamepad, &buttons);
+    }
+    let direction = if settings_menu.open {
+        Vec2::ZERO
+    } else {
+        direction.normalize_or_zero()
+    };
+
+    if !throttle.should_send(direction, time.elapsed_seconds()) {
+        return;
+    }
+
+    for (t, mut player) in query.iter_mut() {
+        steer(
+            t,
+            &mut player,
+            direction,
+            client_tick.tick.unwrap(),
+            &mut outgoing,
+        );
+    }
+}
+
+// drives egui with the keys it already understands: tab moves focus, enter presses
+#[allow(clippy::too_many_arguments)]
+pub fn gamepad_menu_navigation(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    game_stage: Res<State<GameStage>>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut egui_input: Query<&mut EguiInput>,
+    time: Res<Time>,
+    mut repeat: Local<f32>,
+) {
+    let Some(gamepad) = gamepads.iter().next() else {
+        return;
+    };
+    let pressed = |button| buttons.just_pressed(GamepadButton::new(gamepad, button));
+
+    if pressed(GamepadButtonType::Start) {
+        settings_menu.open = !settings_menu.open;
+        settings_menu.server = None;
+        settings_menu.rebinding = None;
+    }
+    if pressed(GamepadButtonType::East) && settings_menu.open {
+        settings_menu.open = false;
+        settings_menu.server = None;
+        settings_menu.rebinding = None;
+    }
+
+    // in game the d-pad and stick move the player instead
+    if *game_stage.get() == GameStage::InGame && !settings_menu.open {
+        return;
+    }
+
+    let mut keys = Vec::new();
+
+    if pressed(GamepadButtonType::DPadDown) {
+        keys.push((egui::Key::Tab, egui::Modifiers::NONE));
+    }
+    if pressed(GamepadButtonType::DPadUp) {
+        keys.push((egui::Key::Tab, egui::Modifiers::SHIFT));
+    }
+    if pressed(GamepadButtonType::DPadLeft) {
+        keys.push((egui::Key::ArrowLeft, egui::Modifiers::NONE));
+    }
+    if pressed(GamepadButtonType::DPadRight) {
+        keys.push((egui::Key::ArrowRight, egui::Modifiers::NONE));
+    }
+    if pressed(GamepadButtonType::South) {
+        keys.push((egui::Key::Enter, egui::Modifiers::NONE));
+    }
+
+    let stick = stick_direction(gamepad, &axes);
+    *repeat -= time.delta_seconds();
+    if stick == Vec2::ZERO {
+        *repeat = 0.0;
+    } else if *repeat <= 0.0 {
+        *repeat = NAV_REPEAT;
+        if stick.y.abs() >= stick.x.abs() {
+            let modifiers = if stick.y > 0.0 {
+                egui::Modifiers::SHIFT
+            } else {
+                egui::Modifiers::NONE
+            };
+            keys.push((egui::Key::Tab, modifiers));
+        } else if stick.x > 0.0 {
+            keys.push((egui::Key::ArrowRight, egui::Modifiers::NONE));
+        } else {
+            keys.push((egui::Key::ArrowLeft, egui::Modifiers::NONE));
+        }
+    }
+
+    if keys.is_empty() {
+        return;
+    }
+
+    for mut input in egui_input.iter_mut() {
+        for (key, modifiers) in keys.iter() {
+            for pressed in [true, false] {
+                input.events.push(egui::Event::Key {
+                    key: *key,
+                    pressed,
+                    repeat: false,
+                    modifiers: *modifiers,
+                });
+            }
+        }
+    }
+}
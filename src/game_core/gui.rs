@@ -10,19 +10,38 @@ use bevy_egui::{
 use crate::{
     game_util::{
         components::NamePlatesLocal,
-        resources::{ClientTick, NetworkStuff, Objects, PingTimer, PlayerName},
+        resources::{ClientTick, NetworkStuff, Objects, PingTimer, PlayerName, MAX_NAME_LENGTH},
+        storage,
+    },
+    network::{
+        lnurl::LightningAddress,
+        messages::{ClientMessage, GameMode, PlayerInput},
     },
-    network::messages::{ClientMessage, PlayerInput},
     GameStage, KeyboardState,
 };
 
-use super::player::{Enemy, Player};
+use super::{
+    leaderboard::{open_leaderboard, top_scores, Leaderboard},
+    lobby::Rooms,
+    modes::{SelectedMode, BOLT_TARGET},
+    player::{Enemy, Player},
+    prize::ln_address_hint,
+    settings::SettingsMenu,
+    spectate::Spectate,
+    stats::{run_summary, Stats},
+    teams::{team_color32, team_name, TEAM_COUNT},
+};
+
+// name, score, color, seconds alive, mode, team
+type ScoreRow = (String, usize, Color32, u64, GameMode, Option<u8>);
 
 pub fn score_board(
     mut contexts: EguiContexts,
     query_player: Query<&Player>,
     query_enemy: Query<&Enemy>,
     player_name: Res<PlayerName>,
+    selected_mode: Res<SelectedMode>,
+    objects: Res<Objects>,
 ) {
     let ctx = contexts.ctx_mut();
 
@@ -32,7 +51,7 @@ pub fn score_board(
 
     ctx.set_style(style);
 
-    let mut score_list: Vec<(String, i32, egui::Color32, u64, u64)> = Vec::new();
+    let mut score_list: Vec<ScoreRow> = Vec::new();
 
     if player_name.submitted {
         for player in query_player.iter() {
@@ -43,14 +62,14 @@ pub fn score_board(
             } else {
                 duration.as_secs()
             };
-            let minutes = seconds / 60;
 
             score_list.push((
                 player.name.to_string(),
-                player.score.try_into().unwrap(),
+                player.score,
                 egui::Color32::GREEN,
                 seconds,
-                minutes,
+                selected_mode.mode,
+                player.team,
             ));
         }
     }
@@ -59,13 +78,13 @@ pub fn score_board(
         if !enemy.name.is_empty() {
             let duration = &enemy.spawn_time;
             let seconds = duration.elapsed_secs() as u64;
-            let minutes = seconds / 60;
             score_list.push((
                 enemy.name.to_string(),
-                enemy.score.try_into().unwrap(),
+                enemy.score,
                 egui::Color32::WHITE,
                 seconds,
-                minutes,
+                enemy.mode,
+                enemy.team,
             ));
         }
     }
@@ -75,18 +94,53 @@ pub fn score_board(
     egui::Area::new("score_board")
         .fixed_pos(egui::pos2(10.0, 10.0))
         .show(ctx, |ui| {
-            for (id, score, color, secs, mins) in score_list {
+            let score_row = |ui: &mut egui::Ui, row: &ScoreRow| {
+                let (id, score, color, secs, mode, _) = row;
                 ui.label(
                     RichText::new(format!(
-                        "{}: {:02}/21⚡ ({:02}:{:02})",
+                        "{}: {}⚡ ({})",
                         id,
-                        score,
-                        mins % 60,
-                        secs % 60,
+                        mode.score_text(*score),
+                        mode.time_text(*secs),
                     ))
-                    .color(color),
+                    .color(*color),
                 );
                 ui.add_space(5.0);
+            };
+
+            if score_list.iter().any(|row| row.5.is_some()) {
+                for team in 0..TEAM_COUNT {
+                    let members: Vec<_> = score_list
+                        .iter()
+                        .filter(|row| row.5 == Some(team))
+                        .collect();
+                    let total = objects
+                        .team_scores
+                        .get(&team)
+                        .copied()
+                        .unwrap_or_else(|| members.iter().map(|row| row.1).sum());
+
+                    ui.label(
+                        RichText::new(format!(
+                            "{}: {:02}/{}⚡",
+                            team_name(team),
+                            total,
+                            BOLT_TARGET
+                        ))
+                        .strong()
+                        .color(team_color32(team)),
+                    );
+                    for row in members {
+                        score_row(ui, row);
+                    }
+                }
+                for row in score_list.iter().filter(|row| row.5.is_none()) {
+                    score_row(ui, row);
+                }
+            } else {
+                for row in score_list.iter() {
+                    score_row(ui, row);
+                }
             }
         });
 }
@@ -98,117 +152,104 @@ pub fn setup_menu(
     mut player_name: ResMut<PlayerName>,
     mut network_stuff: ResMut<NetworkStuff>,
     mut query_player: Query<(&mut Player, &mut Sprite, &Transform)>,
-    client_tick: Res<ClientTick>,
     objects: Res<Objects>,
     mut keyboard_state: ResMut<NextState<KeyboardState>>,
+    mut selected_mode: ResMut<SelectedMode>,
+    mut rooms: ResMut<Rooms>,
+    mut spectate: ResMut<Spectate>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut leaderboard: ResMut<Leaderboard>,
 ) {
-    if client_tick.tick.unwrap_or(0) % 10 == 0 {
-        for (player, _, _) in query_player.iter_mut() {
-            let input = PlayerInput::new([0.0, 0.0], player.id, client_tick.tick.unwrap(), false);
-
-            match network_stuff
-                .write
-                .as_mut()
-                .unwrap()
-                .try_send(ClientMessage::PlayerInput(input))
-            {
-                Ok(()) => {}
-                Err(e) => error!("Error sending message: {} CHANNEL FULL???", e),
-            };
-        }
-    }
-
     let ctx = contexts.ctx_mut();
 
     for (_, mut sprite, _) in query_player.iter_mut() {
         sprite.color = Color::GRAY;
     }
 
+    if let Some(code) = rooms.pending_join.take() {
+        network_stuff.send(ClientMessage::JoinRoom(code));
+        next_state.set(GameStage::Lobby);
+    }
+
+    if settings_menu.open {
+        return;
+    }
+
     egui::Window::new("☔ rain.run              ")
         .resizable(false)
         .collapsible(false)
         .anchor(egui::Align2::CENTER_TOP, egui::Vec2::ZERO)
         .show(ctx, |ui| {
-            ui.label("High Scores");
-            ui.label(format!(
-                "{}\n{}\n{}\n{}\n{}",
-                objects
-                    .high_scores
-                    .get(0)
-                    .map(|(name, score)| format!(
-                        "1: {} ({:02}:{:02})",
-                        name,
-                        score / 60 % 60,
-                        score % 60
-                    ))
-                    .unwrap_or_else(|| "".to_string()),
-                objects
-                    .high_scores
-                    .get(1)
-                    .map(|(name, score)| format!(
-                        "2: {} ({:02}:{:02})",
-                        name,
-                        score / 60 % 60,
-                        score % 60
-                    ))
-                    .unwrap_or_else(|| "".to_string()),
-                objects
-                    .high_scores
-                    .get(2)
-                    .map(|(name, score)| format!(
-                        "3: {} ({:02}:{:02})",
-                        name,
-                        score / 60 % 60,
-                        score % 60
-                    ))
-                    .unwrap_or_else(|| "".to_string()),
-                objects
-                    .high_scores
-                    .get(3)
-                    .map(|(name, score)| format!(
-                        "4: {} ({:02}:{:02})",
-                        name,
-                        score / 60 % 60,
-                        score % 60
-                    ))
-                    .unwrap_or_else(|| "".to_string()),
-                objects
-                    .high_scores
-                    .get(4)
-                    .map(|(name, score)| format!(
-                        "5: {} ({:02}:{:02})",
-                        name,
-                        score / 60 % 60,
-                        score % 60
-                    ))
-                    .unwrap_or_else(|| "".to_string()),
-            ));
+            if let Some(room) = &rooms.current {
+                ui.label(format!(
+                    "Room {} ({}/{}) - share the code to invite friends",
+                    room.code, room.players, room.max_players
+                ));
+            }
+            // a room's mode is fixed when it's created
+            if rooms.current.is_none() {
+                ui.horizontal_wrapped(|ui| {
+                    for mode in GameMode::ALL {
+                        ui.selectable_value(&mut selected_mode.mode, mode, mode.title());
+                    }
+                });
+            }
+            top_scores(
+                ui,
+                selected_mode.mode,
+                objects.high_scores_for(selected_mode.mode),
+            );
+            if ui.button("🏅 Leaderboard").clicked() {
+                open_leaderboard(
+                    &mut leaderboard,
+                    &mut next_state,
+                    selected_mode.mode,
+                    GameStage::Menu,
+                );
+            }
 
-            ui.label("Weekly Challenge 🏆");
-            ui.label("Collect 21 bolts as fast as you can!");
+            ui.label(selected_mode.mode.title());
+            ui.label(selected_mode.mode.description());
             ui.add(
                 TextEdit::singleline(&mut player_name.name)
-                    .char_limit(25)
+                    .char_limit(MAX_NAME_LENGTH)
                     .desired_width(125.0)
-                    .hint_text("Enter Name/LN Addr"),
+                    .hint_text("Enter Name"),
             );
+            ui.add(
+                TextEdit::singleline(&mut player_name.ln_address)
+                    .desired_width(125.0)
+                    .hint_text("⚡ LN address (optional)"),
+            );
+            ln_address_hint(ui, &player_name.ln_address);
             ui.horizontal(|ui| {
                 let mut rand_name = Generator::default();
                 if ui.button("Random Name").clicked() {
                     player_name.name = rand_name.next().unwrap();
                 }
+                if ui.button("Rooms").clicked() {
+                    next_state.set(GameStage::Lobby);
+                }
+                if ui.button("🏆 Tournaments").clicked() {
+                    next_state.set(GameStage::Tournament);
+                }
+                if ui.button("Spectate").clicked() {
+                    keyboard_state.set(KeyboardState::Off);
+                    network_stuff.send(ClientMessage::Spectate(true));
+                    spectate.from_menu = true;
+                    next_state.set(GameStage::Spectating);
+                }
                 if ui.button("Play").clicked() && !player_name.name.is_empty() {
+                    if LightningAddress::parse(&player_name.ln_address).is_ok() {
+                        storage::save("ln_address", &player_name.ln_address);
+                    }
                     keyboard_state.set(KeyboardState::Off);
                     player_name.submitted = true;
-                    match network_stuff
-                        .write
-                        .as_mut()
-                        .unwrap()
-                        .try_send(ClientMessage::PlayerName(player_name.name.clone()))
-                    {
-                        Ok(()) => {}
-                        Err(e) => error!("Error sending message: {} CHANNEL FULL???", e),
-                    };
+                    network_stuff.send(ClientMessage::SelectMode(selected_mode.mode));
+                    if selected_mode.mode == GameMode::Teams {
+                        network_stuff.send(ClientMessage::JoinTeam);
+                    }
+                    network_stuff.send(ClientMessage::PlayerName(player_name.name.clone()));
 
                     for (mut player, _, _) in query_player.iter_mut() {
                         player.spawn_time = Some(Instant::now());
@@ -218,21 +259,65 @@ pub fn setup_menu(
                     next_state.set(GameStage::InGame);
                 }
             });
+            ui.horizontal(|ui| {
+                if ui.button("👤 Profile").clicked() {
+                    next_state.set(GameStage::Profile);
+                }
+                if ui.button("📊 Stats").clicked() {
+                    next_state.set(GameStage::Stats);
+                }
+                if ui.button("⚙ Settings").clicked() {
+                    settings_menu.open = true;
+                }
+            });
         });
 }
 
-pub fn disconnected(mut contexts: EguiContexts) {
+pub fn disconnected(mut contexts: EguiContexts, mut settings_menu: ResMut<SettingsMenu>) {
     let ctx = contexts.ctx_mut();
 
+    if settings_menu.open {
+        return;
+    }
+
     egui::Window::new("☔ rain.run              ")
         .resizable(false)
         .collapsible(false)
         .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
         .show(ctx, |ui| {
             ui.label("disconnected");
+            if ui.button("⚙ Settings").clicked() {
+                settings_menu.open = true;
+            }
         });
 }
 
+// anyone not playing still checks in about once a second so the server keeps the connection
+pub fn keep_alive(
+    mut network_stuff: ResMut<NetworkStuff>,
+    query_player: Query<&Player>,
+    client_tick: Res<ClientTick>,
+    game_stage: Res<State<GameStage>>,
+) {
+    if matches!(
+        game_stage.get(),
+        GameStage::InGame | GameStage::Disconnected
+    ) {
+        return;
+    }
+    let Some(tick) = client_tick.tick else {
+        return;
+    };
+    if tick % 10 != 0 {
+        return;
+    }
+
+    for player in query_player.iter() {
+        let input = PlayerInput::new([0.0, 0.0], player.id, tick, false);
+        network_stuff.send(ClientMessage::PlayerInput(input));
+    }
+}
+
 pub fn check_disconnected(
     mut ping: ResMut<PingTimer>,
     mut next_state: ResMut<NextState<GameStage>>,
@@ -253,24 +338,10 @@ pub fn game_over(
     mut next_state: ResMut<NextState<GameStage>>,
     mut query_text: Query<&mut Text, With<NamePlatesLocal>>,
     objects: Res<Objects>,
-    client_tick: Res<ClientTick>,
+    selected_mode: Res<SelectedMode>,
+    mut leaderboard: ResMut<Leaderboard>,
+    stats: Res<Stats>,
 ) {
-    if client_tick.tick.unwrap_or(0) % 10 == 0 {
-        for (_, player, _) in query_player.iter_mut() {
-            let input = PlayerInput::new([0.0, 0.0], player.id, client_tick.tick.unwrap(), false);
-
-            match network_stuff
-                .write
-                .as_mut()
-                .unwrap()
-                .try_send(ClientMessage::PlayerInput(input))
-            {
-                Ok(()) => {}
-                Err(e) => error!("Error sending message: {} CHANNEL FULL???", e),
-            };
-        }
-    }
-
     let ctx = contexts.ctx_mut();
 
     egui::Window::new("☔ rain.run              ")
@@ -283,100 +354,55 @@ pub fn game_over(
                 player.target = Vec2::ZERO;
                 sprite.color = Color::GRAY;
                 player.pending_inputs.clear();
+                player.bumps.clear();
 
                 egui::Area::new("area")
                     .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::new(0.0, -20.0))
                     .show(ctx, |ui| {
                         ui.horizontal(|ui| {
-                            if player.score == 21 {
-                                ui.label(
-                                    RichText::new("Challenge Complete! 🏆").color(Color32::WHITE),
-                                );
-                            }
                             let seconds = player.death_time.unwrap();
-                            let minutes = seconds / 60;
+                            let result =
+                                selected_mode
+                                    .mode
+                                    .result_text(player.score, seconds, player.won);
+                            if !result.is_empty() {
+                                ui.label(RichText::new(result).color(Color32::WHITE));
+                            }
 
                             for mut text in query_text.iter_mut() {
-                                text.sections[0].value = format!(
-                                    "{:02}/21\n({:02}:{:02})",
-                                    player.score,
-                                    minutes % 60,
-                                    seconds % 60,
-                                );
+                                text.sections[0].value =
+                                    selected_mode.mode.hud_text(player.score, seconds);
                             }
                             if ui.button("Play Again").clicked() {
-                                match network_stuff
-                                    .write
-                                    .as_mut()
-                                    .unwrap()
-                                    .try_send(ClientMessage::PlayerName(player_name.name.clone()))
-                                {
-                                    Ok(()) => {}
-                                    Err(e) => {
-                                        error!("Error sending message: {} CHANNEL FULL???", e)
-                                    }
-                                };
+                                network_stuff
+                                    .send(ClientMessage::PlayerName(player_name.name.clone()));
                                 player.score = 0;
                                 player.spawn_time = Some(Instant::now());
                                 next_state.set(GameStage::InGame);
                                 player.death_time = None;
+                                player.won = false;
+                            }
+                            if ui.button("Spectate").clicked() {
+                                next_state.set(GameStage::Spectating);
                             }
                         });
                     });
-                ui.label("High Scores");
-                ui.label(format!(
-                    "{}\n{}\n{}\n{}\n{}",
-                    objects
-                        .high_scores
-                        .get(0)
-                        .map(|(name, score)| format!(
-                            "1: {} ({:02}:{:02})",
-                            name,
-                            score / 60 % 60,
-                            score % 60
-                        ))
-                        .unwrap_or_else(|| "".to_string()),
-                    objects
-                        .high_scores
-                        .get(1)
-                        .map(|(name, score)| format!(
-                            "2: {} ({:02}:{:02})",
-                            name,
-                            score / 60 % 60,
-                            score % 60
-                        ))
-                        .unwrap_or_else(|| "".to_string()),
-                    objects
-                        .high_scores
-                        .get(2)
-                        .map(|(name, score)| format!(
-                            "3: {} ({:02}:{:02})",
-                            name,
-                            score / 60 % 60,
-                            score % 60
-                        ))
-                        .unwrap_or_else(|| "".to_string()),
-                    objects
-                        .high_scores
-                        .get(3)
-                        .map(|(name, score)| format!(
-                            "4: {} ({:02}:{:02})",
-                            name,
-                            score / 60 % 60,
-                            score % 60
-                        ))
-                        .unwrap_or_else(|| "".to_string()),
-                    objects
-                        .high_scores
-                        .get(4)
-                        .map(|(name, score)| format!(
-                            "5: {} ({:02}:{:02})",
-                            name,
-                            score / 60 % 60,
-                            score % 60
-                        ))
-                        .unwrap_or_else(|| "".to_string()),
-                ));
+                ui.label(selected_mode.mode.title());
+                top_scores(
+                    ui,
+                    selected_mode.mode,
+                    objects.high_scores_for(selected_mode.mode),
+                );
+                if ui.button("🏅 Leaderboard").clicked() {
+                    open_leaderboard(
+                        &mut leaderboard,
+                        &mut next_state,
+                        selected_mode.mode,
+                        GameStage::GameOver,
+                    );
+                }
+                ui.separator();
+                run_summary(ui, &stats);
             }
         });
 }
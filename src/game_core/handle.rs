@@ -6,13 +6,18 @@ use crate::{
     game_core::sprites::{spawn_enemies, spawn_player},
     game_util::{
         components::{Bolt, Rain},
-        resources::{BoltPool, ClientTick, NetworkStuff, Objects, RainPool},
+        events::{
+            BurstKind, CameraShake, ChallengeUpdate, GameEvents, LeaderboardUpdate, ParticleBurst,
+            PrizeUpdate, ProfileUpdate, RoomJoined, RunFinished, SoundEffect, TournamentUpdate,
+        },
+        resources::{BoltPool, ClientTick, NetworkStuff, Objects, PendingRun, RainPool},
     },
     network::messages::NetworkMessage,
     GameStage, KeyboardState,
 };
 
 use super::{
+    lobby::Rooms,
     objects::{handle_bolt_behind, handle_rain_behind, ObjectPos},
     player::{Enemy, Player},
 };
@@ -36,6 +41,8 @@ pub fn handle_server(
     >,
     mut keyboard_state: ResMut<NextState<KeyboardState>>,
     windows: Query<&Window>,
+    (mut rooms, mut pending_run): (ResMut<Rooms>, ResMut<PendingRun>),
+    mut game_events: GameEvents,
 ) {
     if let Some(ref mut receive_rx) = incoming.read {
         while let Ok(Some(message)) = receive_rx.try_next() {
@@ -92,6 +99,9 @@ pub fn handle_server(
                         for (_, mut enemy, _, _) in query_enemy.iter_mut() {
                             if enemy.id == player.id {
                                 enemy.score = player.score;
+                                enemy.mode = player.mode;
+                                enemy.team = player.team;
+                                enemy.alive = player.alive;
                             }
                         }
                         if !existing_entities.contains(&player.id) {
@@ -104,6 +114,8 @@ pub fn handle_server(
                                 player.name,
                                 &asset_server,
                                 player.time_alive,
+                                player.mode,
+                                player.team,
                             );
                         }
                     }
@@ -111,7 +123,9 @@ pub fn handle_server(
                 Ok(NetworkMessage::NewGame(new_game)) => {
                     client_tick.tick = Some(new_game.server_tick);
                     objects.rng_seed = Some(new_game.rng_seed);
-                    objects.high_scores = new_game.high_scores;
+                    objects.high_scores = new_game.high_scores.into_iter().collect();
+                    objects.team_scores.clear();
+                    objects.pvp = new_game.pvp;
 
                     objects.rain_pos = new_game
                         .objects
@@ -133,26 +147,49 @@ pub fn handle_server(
                         })
                         .collect();
 
-                    spawn_player(
-                        &mut commands,
-                        &new_game.id,
-                        &asset_server,
-                        &mut next_state,
-                        &mut keyboard_state,
-                        &windows,
-                    );
+                    // a run started from a menu is already in game, any other new world goes back to the menu
+                    let pending = pending_run.0.take();
+
+                    // joining a room hands us a fresh world, reuse the umbrella we already have
+                    if let Some((mut player, mut t)) = query_player.iter_mut().next() {
+                        player.id = new_game.id;
+                        player.score = 0;
+                        player.target = Vec2::ZERO;
+                        player.pending_inputs.clear();
+                        player.bumps.clear();
+                        player.death_time = None;
+                        player.won = false;
+                        player.team = None;
+                        t.translation = Vec3::ZERO;
+                        if pending.is_none() {
+                            keyboard_state.set(KeyboardState::On);
+                            next_state.set(GameStage::Menu);
+                        }
+                    } else {
+                        spawn_player(
+                            &mut commands,
+                            &new_game.id,
+                            &asset_server,
+                            &mut next_state,
+                            &mut keyboard_state,
+                            &windows,
+                        );
+                    }
                 }
                 Ok(NetworkMessage::DamagePlayer(damage)) => {
-                    if let Some(index) = objects
-                        .rain_pos
-                        .iter()
-                        .position(|object| object.tick == damage.tick.unwrap())
-                    {
-                        objects.rain_pos.remove(index);
+                    // no tick means the run ended by the mode rules rather than rain
+                    if let Some(tick) = damage.tick {
+                        if let Some(index) = objects
+                            .rain_pos
+                            .iter()
+                            .position(|object| object.tick == tick)
+                        {
+                            objects.rain_pos.remove(index);
+                        }
                     }
 
                     if let Some(high_scores) = damage.high_scores {
-                        objects.high_scores = high_scores;
+                        objects.high_scores = high_scores.into_iter().collect();
                     }
 
                     for (mut player, mut t) in query_player.iter_mut() {
@@ -160,6 +197,28 @@ pub fn handle_server(
                             t.translation = Vec3::ZERO;
                             player.death_time = Some(damage.secs_alive);
                             player.score = damage.score;
+                            player.won = damage.won;
+                            if damage.tick.is_some() {
+                                game_events.particles.send(ParticleBurst {
+                                    kind: BurstKind::Splash,
+                                    pos: Vec2::from(damage.pos),
+                                });
+                                game_events.camera_shake.send(CameraShake(0.8));
+                                game_events.sound.send(SoundEffect::Hit);
+                            }
+                            if damage.won {
+                                game_events.particles.send(ParticleBurst {
+                                    kind: BurstKind::Lightning,
+                                    pos: Vec2::from(damage.pos),
+                                });
+                                game_events.sound.send(SoundEffect::Complete);
+                            }
+                            game_events.run_finished.send(RunFinished {
+                                score: damage.score,
+                                secs: damage.secs_alive,
+                                won: damage.won,
+                                by_rain: damage.tick.is_some(),
+                            });
                             player.target = t.translation.truncate();
                             next_state.set(GameStage::GameOver);
                         }
@@ -171,16 +230,37 @@ pub fn handle_server(
                         .iter()
                         .position(|object| object.tick == score.tick)
                     {
-                        objects.bolt_pos.remove(index);
+                        let bolt = objects.bolt_pos.remove(index);
+                        game_events.particles.send(ParticleBurst {
+                            kind: BurstKind::Sparks,
+                            pos: bolt.pos.truncate(),
+                        });
+                    }
+
+                    if let Some((team, team_score)) = score.team_score {
+                        objects.team_scores.insert(team, team_score);
                     }
 
-                    for (mut player, _t) in query_player.iter_mut() {
+                    for (mut player, _) in query_player.iter_mut() {
                         if score.id == player.id {
+                            // the finishing effects come with the won DamagePlayer
+                            if score.score > player.score {
+                                game_events.sound.send(SoundEffect::Pickup);
+                            }
                             player.score = score.score;
                         }
                     }
-                    for (_entity, mut enemy, _t, _) in query_enemy.iter_mut() {
+                    for (_entity, mut enemy, t, _) in query_enemy.iter_mut() {
                         if score.id == enemy.id {
+                            // only a score that ends their run by the mode rules gets the lightning
+                            if enemy.mode.is_won(score.score, false)
+                                && !enemy.mode.is_won(enemy.score, false)
+                            {
+                                game_events.particles.send(ParticleBurst {
+                                    kind: BurstKind::Lightning,
+                                    pos: t.translation.truncate(),
+                                });
+                            }
                             enemy.score = score.score;
                         }
                     }
@@ -219,6 +299,56 @@ pub fn handle_server(
                         }
                     }
                 }
+                Ok(NetworkMessage::RoomList(room_list)) => {
+                    rooms.list = room_list;
+                }
+                Ok(NetworkMessage::RoomJoined(room)) => {
+                    game_events.room_joined.send(RoomJoined(room));
+                }
+                Ok(NetworkMessage::RoomError(error)) => {
+                    rooms.error = Some(error);
+                }
+                Ok(NetworkMessage::Pong) => {
+                    if let Some(ping_sent) = rooms.ping_sent.take() {
+                        rooms.ping_ms = Some(ping_sent.elapsed().as_millis());
+                    }
+                }
+                Ok(NetworkMessage::TeamAssigned(team)) => {
+                    for (mut player, _) in query_player.iter_mut() {
+                        player.team = Some(team);
+                    }
+                }
+                Ok(NetworkMessage::PrizeClaim(status)) => {
+                    game_events.prize.send(PrizeUpdate(status));
+                }
+                Ok(NetworkMessage::Tournament(message)) => {
+                    game_events.tournament.send(TournamentUpdate(message));
+                }
+                Ok(NetworkMessage::Challenge(message)) => {
+                    game_events.challenge.send(ChallengeUpdate(message));
+                }
+                Ok(NetworkMessage::Leaderboard(page)) => {
+                    game_events.leaderboard.send(LeaderboardUpdate(page));
+                }
+                Ok(NetworkMessage::Profile(profile)) => {
+                    game_events.profile.send(ProfileUpdate(profile));
+                }
+                Ok(NetworkMessage::BoltStolen(steal)) => {
+                    for (mut player, _) in query_player.iter_mut() {
+                        if steal.thief == player.id {
+                            player.score = steal.thief_score;
+                        } else if steal.victim == player.id {
+                            player.score = steal.victim_score;
+                        }
+                    }
+                    for (_, mut enemy, _, _) in query_enemy.iter_mut() {
+                        if steal.thief == enemy.id {
+                            enemy.score = steal.thief_score;
+                        } else if steal.victim == enemy.id {
+                            enemy.score = steal.victim_score;
+                        }
+                    }
+                }
                 Ok(NetworkMessage::Ping) => {}
                 Err(_) => {}
             }
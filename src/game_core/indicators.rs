@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui::{self, Color32, Pos2, Shape, Stroke},
+    EguiContexts,
+};
+
+use crate::game_util::resources::Objects;
+
+use super::objects::FALL_SPEED;
+
+const EDGE_MARGIN: f32 = 16.0;
+const ARROW_SIZE: f32 = 12.0;
+const MAX_BOLT_ARROWS: usize = 6;
+// warn about rain that will fall into view within this many ticks
+const DANGER_TICKS: f32 = 30.0;
+
+pub fn offscreen_indicators(
+    mut contexts: EguiContexts,
+    objects: Res<Objects>,
+    camera_query: Query<(&Camera, &GlobalTransform, &OrthographicProjection)>,
+    windows: Query<&Window>,
+) {
+    let Some(window) = windows.iter().next() else {
+        return;
+    };
+    let Ok((camera, camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+
+    let ctx = contexts.ctx_mut();
+    let to_points = window.scale_factor() as f32 / ctx.pixels_per_point();
+    let screen = ctx.screen_rect();
+    let inset = screen.shrink(EDGE_MARGIN);
+    let center = screen.center();
+
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("offscreen_indicators"),
+    ));
+
+    let to_screen = |pos: Vec3| {
+        camera
+            .world_to_viewport(camera_transform, pos)
+            .map(|viewport| Pos2::new(viewport.x * to_points, viewport.y * to_points))
+    };
+
+    let camera_pos = camera_transform.translation();
+    let mut bolts: Vec<_> = objects
+        .bolt_pos
+        .iter()
+        .map(|bolt| {
+            (
+                bolt.pos.truncate().distance(camera_pos.truncate()),
+                bolt.pos,
+            )
+        })
+        .collect();
+    bolts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut arrows = 0;
+    for (_, pos) in bolts {
+        if arrows >= MAX_BOLT_ARROWS {
+            break;
+        }
+        let Some(point) = to_screen(pos) else {
+            continue;
+        };
+        if screen.contains(point) {
+            continue;
+        }
+
+        let direction = (point - center).normalized();
+        let half = inset.size() / 2.0;
+        let reach = (half.x / direction.x.abs()).min(half.y / direction.y.abs());
+        let tip = center + direction * reach;
+        let side = direction.rot90() * ARROW_SIZE / 2.0;
+        let back = tip - direction * ARROW_SIZE;
+
+        painter.add(Shape::convex_polygon(
+            vec![tip, back + side, back - side],
+            Color32::from_rgb(255, 210, 0),
+            Stroke::NONE,
+        ));
+        arrows += 1;
+    }
+
+    let danger_points = FALL_SPEED * DANGER_TICKS / projection.scale * to_points;
+    for rain in objects.rain_pos.iter() {
+        let Some(point) = to_screen(rain.pos) else {
+            continue;
+        };
+        let above = screen.top() - point.y;
+        if above <= 0.0 || above > danger_points || !screen.x_range().contains(&point.x) {
+            continue;
+        }
+
+        let closeness = 1.0 - above / danger_points;
+        let tip = Pos2::new(point.x, screen.top() + EDGE_MARGIN);
+        painter.add(Shape::convex_polygon(
+            vec![
+                tip,
+                tip + egui::vec2(-ARROW_SIZE / 3.0, -ARROW_SIZE / 1.5),
+                tip + egui::vec2(ARROW_SIZE / 3.0, -ARROW_SIZE / 1.5),
+            ],
+            Color32::from_rgba_unmultiplied(255, 60, 60, (80.0 + 175.0 * closeness) as u8),
+            Stroke::NONE,
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy_egui::EguiContexts;
 use virtual_joystick::{
     TintColor, VirtualJoystickEvent, VirtualJoystickEventType, VirtualJoystickNode,
 };
@@ -6,11 +7,23 @@ use virtual_joystick::{
 use crate::{
     game_util::resources::{ClientTick, NetworkStuff},
     network::messages::{ClientMessage, PlayerInput},
+    GameStage,
 };
 
-use super::player::Player;
-use std::f32::consts::PI;
+use super::{
+    objects::X_BOUNDS,
+    player::Player,
+    settings::{ControlScheme, Settings, SettingsMenu},
+    sprites::spawn_joystick,
+};
+
+// only resend when an analog direction turned at least this far (radians)
+const ANGLE_STEP: f32 = 0.06;
+const INPUT_INTERVAL: f32 = 0.1;
+// world units a held pointer has to move before the target is resent
+const MIN_DRAG_DISTANCE: f32 = 10.0;
 
+#[allow(clippy::too_many_arguments)]
 pub fn input(
     mut query: Query<&mut Player>,
     mouse: Res<Input<MouseButton>>,
@@ -19,7 +32,18 @@ pub fn input(
     mut outgoing: ResMut<NetworkStuff>,
     client_tick: Res<ClientTick>,
     touches: Res<Touches>,
+    mut contexts: EguiContexts,
+    settings_menu: Res<SettingsMenu>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+    mut last_drag: Local<(Vec2, f32)>,
 ) {
+    // taps on the pause button or overlay shouldn't steer the player
+    let pointer_scheme = matches!(settings.controls, ControlScheme::Tap | ControlScheme::Drag);
+    if settings_menu.open || !pointer_scheme || contexts.ctx_mut().wants_pointer_input() {
+        return;
+    }
+
     for mut player in query.iter_mut() {
         let (camera, camera_transform) = camera_query.single();
 
@@ -40,140 +64,214 @@ pub fn input(
         };
 
         if client_tick.pause == 0 {
-            let mut handle_input = |cursor_position: Vec2, player: &mut Player| {
-                player.target = cursor_position;
-
-                let input = PlayerInput::new(
-                    [player.target.x, player.target.y],
-                    player.id,
-                    client_tick.tick.unwrap(),
-                    true,
-                );
-
-                player.pending_inputs.push(input.clone());
-
-                match outgoing
-                    .write
-                    .as_mut()
-                    .unwrap()
-                    .try_send(ClientMessage::PlayerInput(input))
-                {
-                    Ok(()) => {}
-                    Err(e) => error!("Error sending message: {} CHANNEL FULL???", e),
+            let now = time.elapsed_seconds();
+            let mut handle_input =
+                |cursor_position: Vec2, player: &mut Player, last_drag: &mut (Vec2, f32)| {
+                    player.target = cursor_position;
+
+                    let input = PlayerInput::new(
+                        [player.target.x, player.target.y],
+                        player.id,
+                        client_tick.tick.unwrap(),
+                        true,
+                    );
+
+                    player.record_input(input.clone());
+                    *last_drag = (cursor_position, now);
+
+                    outgoing.send(ClientMessage::PlayerInput(input));
                 };
+
+            let Some(window) = windows.iter().next() else {
+                continue;
             };
 
+            let mut pressed = None;
             if mouse.just_pressed(MouseButton::Left) || mouse.just_pressed(MouseButton::Right) {
-                if let Some(window) = windows.iter().next() {
-                    if let Some(cursor) = window.cursor_position() {
-                        let position = get_position(cursor, window);
-                        handle_input(position, &mut player);
-                    }
-                }
+                pressed = window.cursor_position();
+            }
+            if let Some(touch) = touches.iter_just_pressed().last() {
+                pressed = Some(touch.position());
             }
 
-            for touch in touches.iter_just_pressed() {
-                if let Some(window) = windows.iter().next() {
-                    let position = get_position(touch.position(), window);
-                    handle_input(position, &mut player);
-                }
+            // the target follows the held pointer, at most once a tick and only
+            // when it moved far enough to matter
+            let mut dragged = None;
+            if settings.controls == ControlScheme::Drag {
+                let held = if mouse.pressed(MouseButton::Left) || mouse.pressed(MouseButton::Right)
+                {
+                    window.cursor_position()
+                } else {
+                    touches.iter().next().map(|touch| touch.position())
+                };
+
+                let (last_target, last_time) = *last_drag;
+                dragged = held
+                    .map(|held| get_position(held, window))
+                    .filter(|position| {
+                        now - last_time >= INPUT_INTERVAL
+                            && position.distance(last_target) >= MIN_DRAG_DISTANCE
+                    });
+            }
+
+            if let Some(position) = pressed
+                .map(|pressed| get_position(pressed, window))
+                .or(dragged)
+            {
+                handle_input(position, &mut player, &mut last_drag);
             }
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn keyboard_input(
+    mut query: Query<(&Transform, &mut Player)>,
+    keys: Res<Input<KeyCode>>,
+    mut outgoing: ResMut<NetworkStuff>,
+    client_tick: Res<ClientTick>,
+    settings: Res<Settings>,
+    settings_menu: Res<SettingsMenu>,
+    mut contexts: EguiContexts,
+    mut last_direction: Local<Vec2>,
+) {
+    if client_tick.pause != 0 || contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    let direction = if settings_menu.open {
+        Vec2::ZERO
+    } else {
+        settings.keys.direction(&keys)
+    };
+
+    if direction == *last_direction {
+        return;
+    }
+    *last_direction = direction;
+
+    for (t, mut player) in query.iter_mut() {
+        steer(
+            t,
+            &mut player,
+            direction,
+            client_tick.tick.unwrap(),
+            &mut outgoing,
+        );
+    }
+}
+
+// aim far past the field edge so the player keeps moving, a zero direction stops in place
+pub fn steer(
+    t: &Transform,
+    player: &mut Player,
+    direction: Vec2,
+    tick: u64,
+    outgoing: &mut NetworkStuff,
+) {
+    player.target = t.translation.truncate() + direction * X_BOUNDS * 2.0;
+
+    let input = PlayerInput::new([player.target.x, player.target.y], player.id, tick, true);
+
+    player.record_input(input.clone());
+    outgoing.send(ClientMessage::PlayerInput(input));
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn update_joystick(
     mut joystick: EventReader<VirtualJoystickEvent<String>>,
     mut joystick_color: Query<(&mut TintColor, &VirtualJoystickNode<String>)>,
     mut query: Query<(&Transform, &mut Player)>,
     mut outgoing: ResMut<NetworkStuff>,
     client_tick: Res<ClientTick>,
+    settings_menu: Res<SettingsMenu>,
+    time: Res<Time>,
+    mut throttle: Local<DirectionThrottle>,
 ) {
-    if client_tick.pause == 0 {
-        for j in joystick.iter() {
-            match j.get_type() {
-                VirtualJoystickEventType::Press | VirtualJoystickEventType::Drag => {
-                    let (mut color, node) = joystick_color.single_mut();
-                    if node.id == j.id() {
-                        *color = TintColor(Color::WHITE);
-                    }
-                }
-                VirtualJoystickEventType::Up => {
-                    let (mut color, node) = joystick_color.single_mut();
-                    if node.id == j.id() {
-                        *color = TintColor(Color::WHITE.with_a(0.2));
-                    }
-                }
-            }
+    if client_tick.pause != 0 {
+        return;
+    }
 
-            for (t, mut player) in query.iter_mut() {
-                let axis = j.axis().normalize();
-
-                let mut should_send = false;
-
-                let mut current_direction = String::from("");
-                let angle = axis.y.atan2(axis.x);
-                let degree = angle * 180.0 / PI;
-                let tolerance = 1.0;
-
-                if degree >= -tolerance && degree <= tolerance {
-                    current_direction = "Right".to_string();
-                } else if degree >= 90.0 - tolerance && degree <= 90.0 + tolerance {
-                    current_direction = "Up".to_string();
-                } else if degree >= -90.0 - tolerance && degree <= -90.0 + tolerance {
-                    current_direction = "Down".to_string();
-                } else if degree >= 180.0 - tolerance || degree <= -180.0 + tolerance {
-                    current_direction = "Left".to_string();
-                } else if degree >= 45.0 - tolerance && degree <= 45.0 + tolerance {
-                    current_direction = "UpRight".to_string();
-                } else if degree >= 135.0 - tolerance && degree <= 135.0 + tolerance {
-                    current_direction = "UpLeft".to_string();
-                } else if degree >= -135.0 - tolerance && degree <= -135.0 + tolerance {
-                    current_direction = "DownLeft".to_string();
-                } else if degree >= -45.0 - tolerance && degree <= -45.0 + tolerance {
-                    current_direction = "DownRight".to_string();
-                }
-
-                if player.last_direction.is_none()
-                    || player.last_direction.as_ref().unwrap() != &current_direction
-                {
-                    should_send = true;
-                    player.last_direction = Some(current_direction.clone());
-                }
-
-                if should_send {
-                    match current_direction.as_str() {
-                        "Up" => player.target = Vec2::new(0.0, 1.0) * 1000.,
-                        "Down" => player.target = Vec2::new(0.0, -1.0) * 1000.,
-                        "Right" => player.target = Vec2::new(1.0, 0.0) * 1000.,
-                        "Left" => player.target = Vec2::new(-1.0, 0.0) * 1000.,
-                        "UpRight" => player.target = Vec2::new(1.0, 1.0) * 1000.,
-                        "UpLeft" => player.target = Vec2::new(-1.0, 1.0) * 1000.,
-                        "DownRight" => player.target = Vec2::new(1.0, -1.0) * 1000.,
-                        "DownLeft" => player.target = Vec2::new(-1.0, -1.0) * 1000.,
-                        _ => player.target = Vec2::new(t.translation.x, t.translation.y),
-                    };
+    // only the latest event of the frame matters for where the knob is
+    let Some(j) = joystick.iter().last() else {
+        return;
+    };
 
-                    let input = PlayerInput::new(
-                        [player.target.x, player.target.y],
-                        player.id,
-                        client_tick.tick.unwrap(),
-                        true,
-                    );
+    let direction = match j.get_type() {
+        VirtualJoystickEventType::Press | VirtualJoystickEventType::Drag => {
+            j.axis().normalize_or_zero()
+        }
+        VirtualJoystickEventType::Up => Vec2::ZERO,
+    };
+    let direction = if settings_menu.open {
+        Vec2::ZERO
+    } else {
+        direction
+    };
 
-                    player.pending_inputs.push(input.clone());
-
-                    match outgoing
-                        .write
-                        .as_mut()
-                        .unwrap()
-                        .try_send(ClientMessage::PlayerInput(input))
-                    {
-                        Ok(()) => {}
-                        Err(e) => error!("Error sending message: {} CHANNEL FULL???", e),
-                    };
-                }
-            }
+    for (mut color, node) in joystick_color.iter_mut() {
+        if node.id == j.id() {
+            *color = if direction == Vec2::ZERO {
+                TintColor(Color::WHITE.with_a(0.2))
+            } else {
+                TintColor(Color::WHITE)
+            };
+        }
+    }
+
+    if !throttle.should_send(direction, time.elapsed_seconds()) {
+        return;
+    }
+
+    for (t, mut player) in query.iter_mut() {
+        steer(
+            t,
+            &mut player,
+            direction,
+            client_tick.tick.unwrap(),
+            &mut outgoing,
+        );
+    }
+}
+
+// the joystick only exists while playing with the joystick scheme selected
+pub fn sync_joystick(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    game_stage: Res<State<GameStage>>,
+    query_joystick: Query<Entity, With<VirtualJoystickNode<String>>>,
+) {
+    let wanted =
+        *game_stage.get() == GameStage::InGame && settings.controls == ControlScheme::Joystick;
+
+    match (wanted, query_joystick.iter().next()) {
+        (true, None) => spawn_joystick(&mut commands, &asset_server),
+        (false, Some(joystick)) => commands.entity(joystick).despawn_recursive(),
+        _ => {}
+    }
+}
+
+// analog sources resend at most once a tick, and only when the direction really changed
+#[derive(Default)]
+pub struct DirectionThrottle {
+    last_direction: Vec2,
+    last_time: f32,
+}
+
+impl DirectionThrottle {
+    pub fn should_send(&mut self, direction: Vec2, now: f32) -> bool {
+        let started_or_stopped = (direction == Vec2::ZERO) != (self.last_direction == Vec2::ZERO);
+        let turned = direction != Vec2::ZERO
+            && self.last_direction != Vec2::ZERO
+            && direction.angle_between(self.last_direction).abs() > ANGLE_STEP;
+
+        if started_or_stopped || (turned && now - self.last_time >= INPUT_INTERVAL) {
+            self.last_direction = direction;
+            self.last_time = now;
+            true
+        } else {
+            false
         }
     }
 }
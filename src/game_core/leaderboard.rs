@@ -0,0 +1,244 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContexts,
+};
+
+use crate::{
+    game_util::{
+        events::LeaderboardUpdate,
+        resources::{NetworkStuff, PlayerName},
+    },
+    network::messages::{
+        ClientMessage, GameMode, LeaderboardEntry, LeaderboardPage, LeaderboardPeriod,
+        LeaderboardQuery,
+    },
+    GameStage,
+};
+
+use super::settings::SettingsMenu;
+
+const PAGE_SIZE: u32 = 10;
+const TOP_SCORES: usize = 5;
+
+#[derive(Resource)]
+pub struct Leaderboard {
+    pub mode: GameMode,
+    pub period: LeaderboardPeriod,
+    pub page: u32,
+    pub current: Option<LeaderboardPage>,
+    pub loading: bool,
+    // where the back button goes
+    pub back: GameStage,
+}
+
+impl Leaderboard {
+    pub fn new() -> Self {
+        Self {
+            mode: GameMode::default(),
+            period: LeaderboardPeriod::default(),
+            page: 0,
+            current: None,
+            loading: false,
+            back: GameStage::Menu,
+        }
+    }
+}
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Leaderboard::new())
+            .add_event::<LeaderboardUpdate>()
+            .add_systems(Update, apply_leaderboard_updates)
+            .add_systems(OnEnter(GameStage::Leaderboard), request_page)
+            .add_systems(
+                Update,
+                leaderboard_screen.run_if(in_state(GameStage::Leaderboard)),
+            );
+    }
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [LeaderboardPeriod; 3] = [
+        LeaderboardPeriod::AllTime,
+        LeaderboardPeriod::Weekly,
+        LeaderboardPeriod::Daily,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            LeaderboardPeriod::AllTime => "All time",
+            LeaderboardPeriod::Weekly => "This week",
+            LeaderboardPeriod::Daily => "Today",
+        }
+    }
+}
+
+// the short list under the menus, the full board is a click away
+pub fn top_scores(ui: &mut egui::Ui, mode: GameMode, high_scores: &[(String, u64)]) {
+    ui.label("High Scores");
+    for (place, (name, score)) in high_scores.iter().take(TOP_SCORES).enumerate() {
+        ui.label(format!(
+            "{}: {} ({})",
+            place + 1,
+            name,
+            mode.format_high_score(*score)
+        ));
+    }
+}
+
+// opens the full board on the given mode, coming back to the current screen
+pub fn open_leaderboard(
+    leaderboard: &mut Leaderboard,
+    next_state: &mut NextState<GameStage>,
+    mode: GameMode,
+    back: GameStage,
+) {
+    leaderboard.mode = mode;
+    leaderboard.page = 0;
+    leaderboard.back = back;
+    next_state.set(GameStage::Leaderboard);
+}
+
+fn send_query(leaderboard: &mut Leaderboard, network_stuff: &mut NetworkStuff, name: &str) {
+    leaderboard.loading = true;
+    network_stuff.send(ClientMessage::Leaderboard(LeaderboardQuery {
+        mode: leaderboard.mode,
+        period: leaderboard.period,
+        page: leaderboard.page,
+        name: name.to_string(),
+    }));
+}
+
+fn request_page(
+    mut leaderboard: ResMut<Leaderboard>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    player_name: Res<PlayerName>,
+) {
+    send_query(&mut leaderboard, &mut network_stuff, &player_name.name);
+}
+
+fn apply_leaderboard_updates(
+    mut leaderboard: ResMut<Leaderboard>,
+    mut updates: EventReader<LeaderboardUpdate>,
+) {
+    for update in updates.iter() {
+        let page = &update.0;
+        // answers to an older query can arrive after the tab already changed
+        if page.mode == leaderboard.mode
+            && page.period == leaderboard.period
+            && page.page == leaderboard.page
+        {
+            leaderboard.current = Some(page.clone());
+            leaderboard.loading = false;
+        }
+    }
+}
+
+fn entry_row(ui: &mut egui::Ui, mode: GameMode, entry: &LeaderboardEntry, own: bool) {
+    let color = if own { Color32::GREEN } else { Color32::WHITE };
+    ui.label(RichText::new(format!("{}", entry.rank)).color(color));
+    ui.label(RichText::new(&entry.name).color(color));
+    ui.label(RichText::new(mode.format_high_score(entry.score)).color(color));
+    ui.end_row();
+}
+
+fn leaderboard_screen(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameStage>>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut leaderboard: ResMut<Leaderboard>,
+    player_name: Res<PlayerName>,
+    settings_menu: Res<SettingsMenu>,
+) {
+    if settings_menu.open {
+        return;
+    }
+
+    let (mut mode, mut period, mut page) = (leaderboard.mode, leaderboard.period, leaderboard.page);
+
+    egui::Window::new("🏅 Leaderboard")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                for tab in LeaderboardPeriod::ALL {
+                    ui.selectable_value(&mut period, tab, tab.title());
+                }
+            });
+            ui.horizontal_wrapped(|ui| {
+                for board in GameMode::ALL {
+                    ui.selectable_value(&mut mode, board, board.title());
+                }
+            });
+            ui.separator();
+
+            match leaderboard.current.as_ref() {
+                Some(current) => {
+                    let own_name = &player_name.name;
+                    egui::Grid::new("leaderboard")
+                        .striped(true)
+                        .min_col_width(40.0)
+                        .show(ui, |ui| {
+                            for entry in current.entries.iter() {
+                                let own = !own_name.is_empty() && entry.name == *own_name;
+                                entry_row(ui, current.mode, entry, own);
+                            }
+
+                            // keep the player's own place visible even when it's on another page
+                            if let Some(own) = current.own.as_ref().filter(|own| {
+                                !current.entries.iter().any(|entry| entry.rank == own.rank)
+                            }) {
+                                ui.label("…");
+                                ui.end_row();
+                                entry_row(ui, current.mode, own, true);
+                            }
+                        });
+
+                    if current.entries.is_empty() {
+                        ui.label("No scores yet");
+                    }
+
+                    let pages = current.total.div_ceil(PAGE_SIZE).max(1);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(page > 0, egui::Button::new("◀")).clicked() {
+                            page -= 1;
+                        }
+                        ui.label(format!("page {} of {}", page + 1, pages));
+                        if ui
+                            .add_enabled(page + 1 < pages, egui::Button::new("▶"))
+                            .clicked()
+                        {
+                            page += 1;
+                        }
+                        if leaderboard.loading {
+                            ui.spinner();
+                        }
+                    });
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+
+            ui.separator();
+            if ui.button("Back").clicked() {
+                next_state.set(leaderboard.back.clone());
+            }
+        });
+
+    // a new board starts from its first page
+    if mode != leaderboard.mode || period != leaderboard.period {
+        page = 0;
+    }
+    if (mode, period, page) != (leaderboard.mode, leaderboard.period, leaderboard.page) {
+        leaderboard.mode = mode;
+        leaderboard.period = period;
+        leaderboard.page = page;
+        send_query(&mut leaderboard, &mut network_stuff, &player_name.name);
+    }
+}
@@ -0,0 +1,304 @@
+use bevy::{prelude::*, utils::Instant};
+
+use bevy_egui::{
+    egui::{self, Color32, RichText, TextEdit},
+    EguiContexts,
+};
+use futures::channel::oneshot;
+
+use crate::{
+    game_util::{
+        events::RoomJoined,
+        resources::{NetworkStuff, PlayerName},
+    },
+    network::{
+        lnurl::{self, LightningAddress, LnurlError},
+        messages::{ClientMessage, CreateRoom, RoomInfo},
+    },
+    GameStage,
+};
+
+use super::{
+    modes::SelectedMode,
+    qr::{invite_from_url, qr_code, room_invite_link, QrCodes},
+    settings::Settings,
+};
+
+const ROOM_CODE_LENGTH: usize = 6;
+const DEFAULT_TIP_SATS: u64 = 100;
+
+#[derive(Resource)]
+pub struct Rooms {
+    pub list: Vec<RoomInfo>,
+    pub current: Option<RoomInfo>,
+    pub code: String,
+    pub pvp: bool,
+    pub error: Option<String>,
+    pub ping_sent: Option<Instant>,
+    pub ping_ms: Option<u128>,
+    // room code from an invite link, joined once the menu is up
+    pub pending_join: Option<String>,
+    // tipping the host of the current room
+    pub tip_sats: u64,
+    pub tip_invoice: Option<String>,
+    pub tip_error: Option<String>,
+    pub tipping: Option<oneshot::Receiver<Result<String, LnurlError>>>,
+}
+
+impl Rooms {
+    pub fn new() -> Self {
+        Self {
+            list: Vec::new(),
+            current: None,
+            code: String::new(),
+            pvp: false,
+            error: None,
+            ping_sent: None,
+            ping_ms: None,
+            pending_join: invite_from_url(),
+            tip_sats: DEFAULT_TIP_SATS,
+            tip_invoice: None,
+            tip_error: None,
+            tipping: None,
+        }
+    }
+
+    fn clear_tip(&mut self) {
+        self.tip_invoice = None;
+        self.tip_error = None;
+        self.tipping = None;
+    }
+
+    fn poll_tip(&mut self) {
+        let Some(result) = self.tipping.as_mut().map(|rx| rx.try_recv()) else {
+            return;
+        };
+        match result {
+            Ok(Some(Ok(invoice))) => self.tip_invoice = Some(invoice),
+            Ok(Some(Err(e))) => self.tip_error = Some(e.to_string()),
+            Ok(None) => return,
+            Err(_) => self.tip_error = Some("lookup was cancelled".to_string()),
+        }
+        self.tipping = None;
+    }
+}
+
+pub fn refresh_rooms(mut network_stuff: ResMut<NetworkStuff>, mut rooms: ResMut<Rooms>) {
+    network_stuff.send(ClientMessage::ListRooms);
+    network_stuff.send(ClientMessage::Ping);
+    rooms.ping_sent = Some(Instant::now());
+}
+
+pub fn apply_room_joined(
+    mut joined: EventReader<RoomJoined>,
+    mut rooms: ResMut<Rooms>,
+    mut selected_mode: ResMut<SelectedMode>,
+) {
+    for RoomJoined(room) in joined.iter() {
+        rooms.error = None;
+        rooms.code.clear();
+        rooms.clear_tip();
+        // the public world is sent as a room without a code
+        if room.code.is_empty() {
+            rooms.current = None;
+        } else {
+            // the room's mode sets the rules, whatever was picked in the menu
+            selected_mode.mode = room.mode;
+            rooms.current = Some(room.clone());
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn lobby(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameStage>>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut rooms: ResMut<Rooms>,
+    selected_mode: Res<SelectedMode>,
+    mut qr_codes: ResMut<QrCodes>,
+    player_name: Res<PlayerName>,
+    settings: Res<Settings>,
+) {
+    rooms.poll_tip();
+
+    let ctx = contexts.ctx_mut();
+
+    egui::Window::new("☔ rain.run              ")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            if let Some(room) = rooms.current.clone() {
+                ui.label(format!("In room {} ({})", room.code, room.mode.title()));
+                if room.private {
+                    let link = room_invite_link(&room.code);
+                    ui.horizontal(|ui| {
+                        qr_code(ui, &mut qr_codes, &link, 120.0);
+                        ui.vertical(|ui| {
+                            ui.label("Scan to join on another device");
+                            ui.label(&link);
+                            if ui.button("Copy link").clicked() {
+                                ui.output_mut(|o| o.copied_text = link.clone());
+                            }
+                        });
+                    });
+                }
+                let host_address = room
+                    .host_address
+                    .as_deref()
+                    .and_then(|address| LightningAddress::parse(address).ok());
+                if let Some(address) = host_address {
+                    tip_host(ui, &mut rooms, &mut qr_codes, &address, &settings);
+                }
+            } else {
+                ui.label("In the public world");
+            }
+
+            if ui
+                .button(format!(
+                    "Create Private Room: {}",
+                    selected_mode.mode.title()
+                ))
+                .clicked()
+            {
+                let host_address = LightningAddress::parse(&player_name.ln_address)
+                    .ok()
+                    .map(|address| address.to_string());
+                network_stuff.send(ClientMessage::CreateRoom(CreateRoom {
+                    mode: selected_mode.mode,
+                    private: true,
+                    pvp: rooms.pvp,
+                    host_address,
+                }));
+            }
+            ui.checkbox(&mut rooms.pvp, "PvP: bump umbrellas and steal bolts");
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    TextEdit::singleline(&mut rooms.code)
+                        .char_limit(ROOM_CODE_LENGTH)
+                        .desired_width(75.0)
+                        .hint_text("Room Code"),
+                );
+                if ui.button("Join").clicked() && !rooms.code.is_empty() {
+                    let code = rooms.code.trim().to_uppercase();
+                    network_stuff.send(ClientMessage::JoinRoom(code));
+                }
+            });
+
+            if let Some(error) = &rooms.error {
+                ui.colored_label(Color32::RED, error);
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Open Rooms");
+                // rooms all live on the same server, so there's one latency for all of them
+                let ping = rooms
+                    .ping_ms
+                    .map(|ping| format!("{}ms", ping))
+                    .unwrap_or_else(|| "-".to_string());
+                ui.label(RichText::new(format!("Server ping: {}", ping)).color(Color32::GRAY));
+            });
+
+            let mut join = None;
+
+            egui::Grid::new("room_list").striped(true).show(ui, |ui| {
+                ui.label("Room");
+                ui.label("Mode");
+                ui.label("Players");
+                ui.label("PvP");
+                ui.end_row();
+
+                for room in rooms.list.iter() {
+                    ui.label(&room.code);
+                    ui.label(room.mode.title());
+                    ui.label(format!("{}/{}", room.players, room.max_players));
+                    ui.label(if room.pvp { "on" } else { "off" });
+                    if ui
+                        .add_enabled(room.players < room.max_players, egui::Button::new("Join"))
+                        .clicked()
+                    {
+                        join = Some(room.code.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+
+            if rooms.list.is_empty() {
+                ui.label("No open rooms");
+            }
+
+            if let Some(code) = join {
+                network_stuff.send(ClientMessage::JoinRoom(code));
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Refresh").clicked() {
+                    network_stuff.send(ClientMessage::ListRooms);
+                    network_stuff.send(ClientMessage::Ping);
+                    rooms.ping_sent = Some(Instant::now());
+                }
+                if rooms.current.is_some() && ui.button("Public World").clicked() {
+                    network_stuff.send(ClientMessage::LeaveRoom);
+                }
+                if ui.button("Back").clicked() {
+                    next_state.set(GameStage::Menu);
+                }
+            });
+        });
+}
+
+// the host's lightning address comes with the room, the invoice is asked of their wallet directly
+fn tip_host(
+    ui: &mut egui::Ui,
+    rooms: &mut Rooms,
+    qr_codes: &mut QrCodes,
+    address: &LightningAddress,
+    settings: &Settings,
+) {
+    ui.separator();
+    ui.label(format!("Tip the host at {}", address));
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut rooms.tip_sats)
+                .clamp_range(1..=100_000)
+                .suffix(" sats"),
+        );
+        if ui
+            .add_enabled(rooms.tipping.is_none(), egui::Button::new("Make invoice"))
+            .clicked()
+        {
+            rooms.clear_tip();
+            rooms.tipping = Some(lnurl::invoice(
+                address,
+                &settings.lnurl_endpoint,
+                rooms.tip_sats,
+            ));
+        }
+    });
+
+    if rooms.tipping.is_some() {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label("Asking the host's wallet…");
+        });
+    }
+    if let Some(error) = &rooms.tip_error {
+        ui.colored_label(Color32::LIGHT_RED, error);
+    }
+    if let Some(invoice) = &rooms.tip_invoice {
+        let uri = format!("lightning:{}", invoice);
+        ui.horizontal(|ui| {
+            qr_code(ui, qr_codes, &uri, 160.0);
+            ui.vertical(|ui| {
+                ui.label("Scan to pay the tip");
+                if ui.button("Copy invoice").clicked() {
+                    ui.output_mut(|o| o.copied_text = invoice.clone());
+                }
+            });
+        });
+    }
+}
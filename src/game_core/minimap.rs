@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui::{self, Color32, Pos2, Rect, Sense, Stroke},
+    EguiContexts,
+};
+
+use crate::game_util::resources::Objects;
+
+use super::{
+    objects::{X_BOUNDS, Y_BOUNDS},
+    player::{Enemy, Player},
+    teams::team_color32,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Resource)]
+pub struct Minimap {
+    pub enabled: bool,
+    pub size: f32,
+    pub corner: Corner,
+}
+
+impl Minimap {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            size: 200.0,
+            corner: Corner::TopRight,
+        }
+    }
+}
+
+const MINIMAP_SIZES: [(&str, f32); 3] = [("Small", 140.0), ("Medium", 200.0), ("Large", 300.0)];
+const CORNERS: [(&str, Corner); 4] = [
+    ("Top Left", Corner::TopLeft),
+    ("Top Right", Corner::TopRight),
+    ("Bottom Left", Corner::BottomLeft),
+    ("Bottom Right", Corner::BottomRight),
+];
+
+pub fn minimap(
+    mut contexts: EguiContexts,
+    mut minimap: ResMut<Minimap>,
+    keys: Res<Input<KeyCode>>,
+    objects: Res<Objects>,
+    query_player: Query<(&Transform, &Player)>,
+    query_enemy: Query<(&Transform, &Enemy)>,
+) {
+    let ctx = contexts.ctx_mut();
+
+    if keys.just_pressed(KeyCode::M) && !ctx.wants_keyboard_input() {
+        minimap.enabled = !minimap.enabled;
+    }
+
+    let (anchor, offset) = match minimap.corner {
+        Corner::TopLeft => (egui::Align2::LEFT_TOP, egui::vec2(10.0, 10.0)),
+        Corner::TopRight => (egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 10.0)),
+        Corner::BottomLeft => (egui::Align2::LEFT_BOTTOM, egui::vec2(10.0, -10.0)),
+        Corner::BottomRight => (egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0)),
+    };
+
+    egui::Area::new("minimap")
+        .anchor(anchor, offset)
+        .show(ctx, |ui| {
+            if !minimap.enabled {
+                if ui.button("🗺").clicked() {
+                    minimap.enabled = true;
+                }
+                return;
+            }
+
+            let size = egui::vec2(minimap.size, minimap.size * Y_BOUNDS / X_BOUNDS);
+            let (response, painter) = ui.allocate_painter(size, Sense::click());
+            let rect = response.rect;
+
+            let to_map = |pos: Vec3| {
+                Pos2::new(
+                    rect.left() + (pos.x + X_BOUNDS) / (X_BOUNDS * 2.0) * rect.width(),
+                    rect.top() + (Y_BOUNDS - pos.y) / (Y_BOUNDS * 2.0) * rect.height(),
+                )
+            };
+
+            painter.rect_filled(rect, 2.0, Color32::from_black_alpha(160));
+            painter.rect_stroke(rect, 2.0, Stroke::new(1.0, Color32::GRAY));
+
+            for bolt in objects.bolt_pos.iter() {
+                painter.circle_filled(to_map(bolt.pos), 1.5, Color32::from_rgb(255, 210, 0));
+            }
+
+            for (t, enemy) in query_enemy.iter() {
+                if enemy.alive && !enemy.name.is_empty() {
+                    let color = enemy.team.map(team_color32).unwrap_or(Color32::WHITE);
+                    painter.circle_filled(to_map(t.translation), 2.5, color);
+                }
+            }
+
+            for (t, _) in query_player.iter() {
+                let pos = to_map(t.translation);
+                painter.circle_filled(pos, 3.5, Color32::GREEN);
+                painter.rect_stroke(
+                    Rect::from_center_size(pos, egui::vec2(9.0, 9.0)),
+                    0.0,
+                    Stroke::new(1.0, Color32::GREEN),
+                );
+            }
+
+            response
+                .on_hover_text("M to hide, right click for options")
+                .context_menu(|ui| {
+                    for (label, size) in MINIMAP_SIZES {
+                        if ui.radio(minimap.size == size, label).clicked() {
+                            minimap.size = size;
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    for (label, corner) in CORNERS {
+                        if ui.radio(minimap.corner == corner, label).clicked() {
+                            minimap.corner = corner;
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("Hide").clicked() {
+                        minimap.enabled = false;
+                        ui.close_menu();
+                    }
+                });
+        });
+}
@@ -1,7 +1,28 @@
+pub mod audio;
+pub mod camera;
+pub mod challenge;
+pub mod daily;
 pub mod game_loop;
+pub mod gamepad;
 pub mod gui;
 pub mod handle;
+pub mod indicators;
 pub mod input;
+pub mod leaderboard;
+pub mod lobby;
+pub mod minimap;
+pub mod modes;
+pub mod nostr;
 pub mod objects;
+pub mod particles;
 pub mod player;
+pub mod prize;
+pub mod profile;
+pub mod pvp;
+pub mod qr;
+pub mod settings;
+pub mod spectate;
 pub mod sprites;
+pub mod stats;
+pub mod teams;
+pub mod tournament;
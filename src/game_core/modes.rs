@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+
+use crate::network::messages::GameMode;
+
+pub const BOLT_TARGET: usize = 21;
+pub const SCORE_ATTACK_SECS: u64 = 120;
+
+#[derive(Resource)]
+pub struct SelectedMode {
+    pub mode: GameMode,
+}
+
+impl SelectedMode {
+    pub fn new() -> Self {
+        Self {
+            mode: GameMode::default(),
+        }
+    }
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 5] = [
+        GameMode::TimeAttack,
+        GameMode::Endless,
+        GameMode::ScoreAttack,
+        GameMode::LastStanding,
+        GameMode::Teams,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            GameMode::TimeAttack => "Weekly Challenge 🏆",
+            GameMode::Endless => "Endless ☔",
+            GameMode::ScoreAttack => "Score Attack ⚡",
+            GameMode::LastStanding => "Last Umbrella Standing ☂",
+            GameMode::Teams => "Teams 🤝",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            GameMode::TimeAttack => "Collect 21 bolts as fast as you can!",
+            GameMode::Endless => "Stay dry for as long as you can!",
+            GameMode::ScoreAttack => "Collect as many bolts as you can in 2 minutes!",
+            GameMode::LastStanding => "Be the last umbrella left in the rain!",
+            GameMode::Teams => "Pool your bolts and be the first team to 21!",
+        }
+    }
+
+    // rain always ends a run, these are the extra ways a run can finish
+    pub fn is_over(&self, score: usize, secs: u64) -> bool {
+        match self {
+            GameMode::TimeAttack => score >= BOLT_TARGET,
+            GameMode::ScoreAttack => secs >= SCORE_ATTACK_SECS,
+            GameMode::Endless | GameMode::LastStanding | GameMode::Teams => false,
+        }
+    }
+
+    pub fn is_won(&self, score: usize, won: bool) -> bool {
+        match self {
+            GameMode::TimeAttack => score >= BOLT_TARGET,
+            GameMode::LastStanding | GameMode::Teams => won,
+            GameMode::Endless | GameMode::ScoreAttack => false,
+        }
+    }
+
+    pub fn result_text(&self, score: usize, secs: u64, won: bool) -> String {
+        match self {
+            GameMode::TimeAttack if self.is_won(score, won) => "Challenge Complete! 🏆".to_string(),
+            GameMode::LastStanding if self.is_won(score, won) => {
+                "Last One Standing! 🏆".to_string()
+            }
+            GameMode::Teams if self.is_won(score, won) => "Your Team Won! 🏆".to_string(),
+            GameMode::Endless => format!("Survived {}", format_secs(secs)),
+            GameMode::ScoreAttack => format!("{} bolts ⚡", score),
+            _ => String::new(),
+        }
+    }
+
+    pub fn score_text(&self, score: usize) -> String {
+        match self {
+            GameMode::TimeAttack => format!("{:02}/{}", score, BOLT_TARGET),
+            _ => format!("{:02}", score),
+        }
+    }
+
+    pub fn time_text(&self, secs: u64) -> String {
+        match self {
+            GameMode::ScoreAttack => format_secs(SCORE_ATTACK_SECS.saturating_sub(secs)),
+            _ => format_secs(secs),
+        }
+    }
+
+    pub fn hud_text(&self, score: usize, secs: u64) -> String {
+        format!("{}\n({})", self.score_text(score), self.time_text(secs))
+    }
+
+    // the value a run is ranked by, in the same units format_high_score expects
+    pub fn high_score_value(&self, score: usize, secs: u64, won: bool) -> Option<u64> {
+        match self {
+            GameMode::TimeAttack => self.is_won(score, won).then_some(secs),
+            GameMode::ScoreAttack => Some(score as u64),
+            GameMode::Endless | GameMode::LastStanding => Some(secs),
+            GameMode::Teams => None,
+        }
+    }
+
+    // high scores are seconds for the timed modes and bolts for score attack
+    pub fn format_high_score(&self, value: u64) -> String {
+        match self {
+            GameMode::ScoreAttack => format!("{}⚡", value),
+            _ => format_secs(value),
+        }
+    }
+}
+
+pub fn format_secs(secs: u64) -> String {
+    format!("{:02}:{:02}", secs / 60 % 60, secs % 60)
+}
+
+// coarse countdown for schedules, a day away doesn't need seconds
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_durations_coarsely() {
+        assert_eq!(format_duration(-5), "0m 0s");
+        assert_eq!(format_duration(59), "0m 59s");
+        assert_eq!(format_duration(3599), "59m 59s");
+        assert_eq!(format_duration(3661), "1h 1m");
+        assert_eq!(format_duration(90061), "1d 1h");
+    }
+
+    #[test]
+    fn formats_clock_time() {
+        assert_eq!(format_secs(0), "00:00");
+        assert_eq!(format_secs(75), "01:15");
+    }
+}
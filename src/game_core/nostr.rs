@@ -0,0 +1,288 @@
+use bevy::{prelude::*, utils::Instant};
+
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContexts,
+};
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_util::{
+        resources::{NetworkStuff, PlayerName, MAX_NAME_LENGTH},
+        storage,
+    },
+    network::{
+        messages::ClientMessage,
+        nostr::{
+            self, Event, LocalKey, LoginResult, NostrError, Signer, UnsignedEvent, SCORE_KIND,
+            SCORE_TAG,
+        },
+    },
+    GameStage,
+};
+
+use super::{modes::SelectedMode, player::Player, settings::Settings};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NostrMethod {
+    Extension,
+    LocalKey,
+}
+
+#[derive(Resource)]
+pub struct Nostr {
+    // remembered so the next session logs in the same way
+    pub method: Option<NostrMethod>,
+    pub signer: Option<Signer>,
+    pub pubkey: Option<String>,
+    pub display_name: Option<String>,
+    pub status: Option<String>,
+    pub login: Option<oneshot::Receiver<LoginResult>>,
+    pub signing: Option<oneshot::Receiver<Result<Event, NostrError>>>,
+    pub publishing: Option<oneshot::Receiver<Result<(), NostrError>>>,
+}
+
+impl Nostr {
+    pub fn new() -> Self {
+        Self {
+            method: storage::load("nostr"),
+            signer: None,
+            pubkey: None,
+            display_name: None,
+            status: None,
+            login: None,
+            signing: None,
+            publishing: None,
+        }
+    }
+}
+
+pub struct NostrPlugin;
+
+impl Plugin for NostrPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Nostr::new())
+            .add_systems(Startup, restore_nostr_login)
+            .add_systems(Update, poll_nostr)
+            .add_systems(OnEnter(GameStage::GameOver), sign_score)
+            .add_systems(Update, nostr_status.run_if(in_state(GameStage::GameOver)));
+    }
+}
+
+fn local_key() -> LocalKey {
+    storage::load::<String>("nostr_key")
+        .and_then(|secret| LocalKey::from_hex(&secret).ok())
+        .unwrap_or_else(|| {
+            let key = LocalKey::generate();
+            storage::save("nostr_key", &key.secret_hex());
+            key
+        })
+}
+
+pub fn start_login(nostr: &mut Nostr, method: NostrMethod, relay: &str) {
+    let signer = match method {
+        NostrMethod::Extension => Signer::Extension,
+        NostrMethod::LocalKey => Signer::Local(local_key()),
+    };
+
+    nostr.login = Some(nostr::login(signer.clone(), relay));
+    nostr.signer = Some(signer);
+    nostr.method = Some(method);
+    nostr.status = None;
+    storage::save("nostr", &method);
+}
+
+pub fn logout(nostr: &mut Nostr, network_stuff: &mut NetworkStuff) {
+    // the server would otherwise keep the key bound to this connection
+    if nostr.pubkey.is_some() && network_stuff.write.is_some() {
+        network_stuff.send(ClientMessage::NostrLogout);
+    }
+    storage::save("nostr", &None::<NostrMethod>);
+    *nostr = Nostr::new();
+}
+
+fn restore_nostr_login(mut nostr: ResMut<Nostr>, settings: Res<Settings>) {
+    if let Some(method) = nostr.method {
+        start_login(&mut nostr, method, &settings.nostr_relay);
+    }
+}
+
+fn poll_nostr(
+    mut nostr: ResMut<Nostr>,
+    mut player_name: ResMut<PlayerName>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    settings: Res<Settings>,
+) {
+    if let Some(result) = nostr.login.as_mut().map(|rx| rx.try_recv()) {
+        match result {
+            Ok(Some(Ok((pubkey, name)))) => {
+                let name = name.unwrap_or_else(|e| {
+                    nostr.status = Some(format!("logged in without a profile name, {}", e));
+                    None
+                });
+                // the profile name is what others see on the name plate
+                if let Some(name) = &name {
+                    player_name.name = name.chars().take(MAX_NAME_LENGTH).collect();
+                }
+                if network_stuff.write.is_some() {
+                    network_stuff.send(ClientMessage::NostrLogin(pubkey.clone()));
+                }
+                nostr.pubkey = Some(pubkey);
+                nostr.display_name = name;
+                nostr.login = None;
+            }
+            Ok(Some(Err(e))) => {
+                nostr.status = Some(e.to_string());
+                nostr.signer = None;
+                nostr.login = None;
+            }
+            Ok(None) => {}
+            Err(_) => {
+                nostr.signer = None;
+                nostr.login = None;
+            }
+        }
+    }
+
+    if let Some(result) = nostr.signing.as_mut().map(|rx| rx.try_recv()) {
+        match result {
+            Ok(Some(Ok(event))) => {
+                if let Ok(json) = serde_json::to_string(&event) {
+                    network_stuff.send(ClientMessage::NostrScore(json));
+                }
+                if settings.nostr_relay.trim().is_empty() {
+                    nostr.status = Some("Score signed".to_string());
+                } else {
+                    nostr.status = Some("Publishing to relay…".to_string());
+                    nostr.publishing = Some(nostr::publish_to(&settings.nostr_relay, event));
+                }
+                nostr.signing = None;
+            }
+            Ok(Some(Err(e))) => {
+                nostr.status = Some(e.to_string());
+                nostr.signing = None;
+            }
+            Ok(None) => {}
+            Err(_) => nostr.signing = None,
+        }
+    }
+
+    if let Some(result) = nostr.publishing.as_mut().map(|rx| rx.try_recv()) {
+        match result {
+            Ok(Some(Ok(()))) => {
+                nostr.status = Some("Score published to relay".to_string());
+                nostr.publishing = None;
+            }
+            Ok(Some(Err(e))) => {
+                nostr.status = Some(e.to_string());
+                nostr.publishing = None;
+            }
+            Ok(None) => {}
+            Err(_) => nostr.publishing = None,
+        }
+    }
+}
+
+fn sign_score(
+    mut nostr: ResMut<Nostr>,
+    selected_mode: Res<SelectedMode>,
+    query_player: Query<&Player>,
+    mut signed_run: Local<Option<Instant>>,
+) {
+    let (Some(signer), Some(pubkey)) = (nostr.signer.clone(), nostr.pubkey.clone()) else {
+        return;
+    };
+    let Some(player) = query_player.iter().next() else {
+        return;
+    };
+    // coming back from the leaderboard enters game over again for the same run
+    if player.spawn_time.is_none() || *signed_run == player.spawn_time {
+        return;
+    }
+    *signed_run = player.spawn_time;
+    let mode = selected_mode.mode;
+    let secs = player.death_time.unwrap_or(0);
+    let Some(value) = mode.high_score_value(player.score, secs, player.won) else {
+        return;
+    };
+
+    let event = UnsignedEvent {
+        pubkey,
+        created_at: nostr::unix_now(),
+        kind: SCORE_KIND,
+        tags: vec![
+            vec!["t".to_string(), SCORE_TAG.to_string()],
+            vec!["mode".to_string(), format!("{:?}", mode)],
+            vec!["score".to_string(), value.to_string()],
+        ],
+        content: format!(
+            "☔ {} in {} on rain.run #{}",
+            mode.format_high_score(value),
+            mode.title(),
+            SCORE_TAG
+        ),
+    };
+
+    nostr.status = Some("Signing score…".to_string());
+    nostr.signing = Some(nostr::sign(signer, event));
+}
+
+fn nostr_status(mut contexts: EguiContexts, nostr: Res<Nostr>) {
+    let Some(status) = &nostr.status else {
+        return;
+    };
+
+    egui::Area::new("nostr_status")
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::Vec2::new(10.0, -10.0))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(RichText::new(format!("🟣 {}", status)).color(Color32::LIGHT_GRAY));
+        });
+}
+
+// login controls for the settings window, the relay edits the settings clone
+pub fn nostr_settings(
+    ui: &mut egui::Ui,
+    nostr: &mut Nostr,
+    network_stuff: &mut NetworkStuff,
+    settings: &mut Settings,
+) {
+    ui.label("Relay for signed scores, leave empty to only send them to the game server");
+    ui.add(egui::TextEdit::singleline(&mut settings.nostr_relay).hint_text("ws://localhost:7777"));
+
+    match (&nostr.pubkey, nostr.login.is_some()) {
+        (Some(pubkey), _) => {
+            let short: String = pubkey.chars().take(12).collect();
+            ui.label(format!(
+                "Logged in as {} ({}…)",
+                nostr.display_name.as_deref().unwrap_or("anon"),
+                short
+            ));
+            if ui.button("Log out").clicked() {
+                logout(nostr, network_stuff);
+            }
+        }
+        (None, true) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Logging in…");
+            });
+        }
+        (None, false) => {
+            ui.horizontal(|ui| {
+                // browsers sign with an extension, other builds keep a key of their own
+                if cfg!(target_arch = "wasm32") {
+                    if ui.button("Login with extension").clicked() {
+                        start_login(nostr, NostrMethod::Extension, &settings.nostr_relay);
+                    }
+                } else if ui.button("Use local key").clicked() {
+                    start_login(nostr, NostrMethod::LocalKey, &settings.nostr_relay);
+                }
+            });
+            if let Some(status) = &nostr.status {
+                ui.label(RichText::new(status).color(Color32::LIGHT_RED));
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::{collections::VecDeque, f32::consts::TAU};
+
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Color32},
+    EguiContexts,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::game_util::{
+    components::Particle,
+    events::{BurstKind, ParticleBurst},
+};
+
+const PARTICLE_COUNT: usize = 400;
+const PARTICLE_SIZE: Vec2 = Vec2::new(3., 3.);
+const FLASH_DECAY: f32 = 3.0;
+const BOLT_HEIGHT: f32 = 400.0;
+
+#[derive(Resource)]
+pub struct ParticlePool(pub VecDeque<Entity>);
+
+#[derive(Resource)]
+pub struct LightningFlash(pub f32);
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ParticlePool(VecDeque::new()))
+            .insert_resource(LightningFlash(0.0))
+            .add_event::<ParticleBurst>()
+            .add_systems(Startup, pool_particles)
+            .add_systems(
+                Update,
+                (spawn_bursts, update_particles, lightning_flash).chain(),
+            );
+    }
+}
+
+fn pool_particles(mut commands: Commands, mut particle_pool: ResMut<ParticlePool>) {
+    for _ in 0..PARTICLE_COUNT {
+        let particle = commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(PARTICLE_SIZE),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Particle::default())
+            .insert(Visibility::Hidden)
+            .id();
+        particle_pool.0.push_back(particle);
+    }
+}
+
+fn spawn_bursts(
+    mut bursts: EventReader<ParticleBurst>,
+    mut particle_pool: ResMut<ParticlePool>,
+    mut particles: Query<(&mut Particle, &mut Visibility, &mut Transform)>,
+    mut flash: ResMut<LightningFlash>,
+) {
+    for burst in bursts.iter() {
+        let seed = (burst.pos.x.to_bits() as u64) << 32 | burst.pos.y.to_bits() as u64;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let (count, life, gravity) = match burst.kind {
+            BurstKind::Splash => (12, 0.5, -200.0),
+            BurstKind::Sparks => (16, 0.4, 0.0),
+            BurstKind::Lightning => (30, 0.35, 0.0),
+        };
+
+        if burst.kind == BurstKind::Lightning {
+            flash.0 = 1.0;
+        }
+
+        let mut bolt_x = burst.pos.x;
+
+        for i in 0..count {
+            // the oldest particle gets reused when the pool runs out
+            let Some(entity) = particle_pool.0.pop_front() else {
+                break;
+            };
+            particle_pool.0.push_back(entity);
+
+            let Ok((mut particle, mut visibility, mut transform)) = particles.get_mut(entity)
+            else {
+                continue;
+            };
+
+            let (pos, velocity, color) = match burst.kind {
+                BurstKind::Splash => {
+                    let angle = rng.gen_range(0.15..0.85) * TAU / 2.0;
+                    let speed = rng.gen_range(40.0..90.0);
+                    (
+                        burst.pos,
+                        Vec2::from_angle(angle) * speed,
+                        Color::rgb(0.5, 0.7, 1.0),
+                    )
+                }
+                BurstKind::Sparks => {
+                    let angle = rng.gen_range(0.0..TAU);
+                    let speed = rng.gen_range(60.0..140.0);
+                    let color = if i % 2 == 0 {
+                        Color::YELLOW
+                    } else {
+                        Color::ORANGE
+                    };
+                    (burst.pos, Vec2::from_angle(angle) * speed, color)
+                }
+                BurstKind::Lightning => {
+                    // a jagged line from above down to whoever got the last bolt
+                    let height = BOLT_HEIGHT * (1.0 - i as f32 / count as f32);
+                    bolt_x += rng.gen_range(-8.0..8.0);
+                    (
+                        Vec2::new(bolt_x, burst.pos.y + height),
+                        Vec2::ZERO,
+                        Color::WHITE,
+                    )
+                }
+            };
+
+            *particle = Particle {
+                velocity,
+                gravity,
+                life,
+                max_life: life,
+                color,
+            };
+            transform.translation = pos.extend(2.0);
+            *visibility = Visibility::Visible;
+        }
+    }
+}
+
+fn update_particles(
+    time: Res<Time>,
+    mut particles: Query<(&mut Particle, &mut Visibility, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut particle, mut visibility, mut transform, mut sprite) in particles.iter_mut() {
+        if particle.life <= 0.0 {
+            continue;
+        }
+
+        particle.life -= dt;
+        if particle.life <= 0.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        particle.velocity.y += particle.gravity * dt;
+        transform.translation += (particle.velocity * dt).extend(0.0);
+        sprite.color = particle.color.with_a(particle.life / particle.max_life);
+    }
+}
+
+fn lightning_flash(mut contexts: EguiContexts, time: Res<Time>, mut flash: ResMut<LightningFlash>) {
+    if flash.0 <= 0.0 {
+        return;
+    }
+
+    let ctx = contexts.ctx_mut();
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("lightning_flash"),
+    ));
+    let alpha = (flash.0 * flash.0 * 180.0) as u8;
+    painter.rect_filled(
+        ctx.screen_rect(),
+        0.0,
+        Color32::from_rgba_unmultiplied(255, 255, 255, alpha),
+    );
+
+    flash.0 = (flash.0 - FLASH_DECAY * time.delta_seconds()).max(0.0);
+}
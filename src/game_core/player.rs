@@ -7,7 +7,10 @@ use bevy::{
 };
 use uuid::Uuid;
 
-use crate::{game_util::resources::ClientTick, network::messages::PlayerInput};
+use crate::{
+    game_util::resources::ClientTick,
+    network::messages::{GameMode, PlayerInput},
+};
 
 use super::objects::{X_BOUNDS, Y_BOUNDS};
 
@@ -15,13 +18,16 @@ pub const PLAYER_SPEED: f32 = 2.5;
 #[derive(Component)]
 pub struct Player {
     pub target: Vec2,
-    pub last_direction: Option<String>,
     pub id: Uuid,
     pub score: usize,
     pub pending_inputs: Vec<PlayerInput>,
+    // pvp pushes by tick, replayed with the inputs so reconciliation lands where prediction did
+    pub bumps: HashMap<u64, Vec2>,
     pub name: String,
     pub spawn_time: Option<Instant>,
     pub death_time: Option<u64>,
+    pub won: bool,
+    pub team: Option<u8>,
 }
 
 impl Player {
@@ -34,6 +40,7 @@ impl Player {
     ) {
         self.pending_inputs
             .retain(|input| input.tick >= server_tick);
+        self.bumps.retain(|tick, _| *tick >= server_tick);
 
         t.translation.x = pos[0];
         t.translation.y = pos[1];
@@ -48,6 +55,27 @@ impl Player {
                 self.target.y = tick_input.target[1];
             }
             self.apply_input(t, client_tick);
+            if let Some(push) = self.bumps.get(&sim_tick) {
+                push_within_bounds(t, *push);
+            }
+        }
+    }
+
+    pub fn apply_bump(&mut self, t: &mut Transform, push: Vec2, tick: u64) {
+        self.bumps.insert(tick, push);
+        push_within_bounds(t, push);
+    }
+
+    // the server keeps the last input it got for a tick, so replay must do the same
+    pub fn record_input(&mut self, input: PlayerInput) {
+        if let Some(pending) = self
+            .pending_inputs
+            .iter_mut()
+            .find(|pending| pending.tick == input.tick)
+        {
+            *pending = input;
+        } else {
+            self.pending_inputs.push(input);
         }
     }
 
@@ -80,12 +108,20 @@ impl Player {
     }
 }
 
+fn push_within_bounds(t: &mut Transform, push: Vec2) {
+    t.translation.x = (t.translation.x + push.x).clamp(-X_BOUNDS, X_BOUNDS);
+    t.translation.y = (t.translation.y + push.y).clamp(-Y_BOUNDS, Y_BOUNDS);
+}
+
 #[derive(Component)]
 pub struct Enemy {
     pub target: Vec2,
     pub id: Uuid,
     pub score: usize,
     pub name: String,
+    pub mode: GameMode,
+    pub team: Option<u8>,
+    pub alive: bool,
     pub spawn_time: Stopwatch,
     pub past_pos: HashMap<u64, Vec3>,
     pub pending_inputs: VecDeque<PlayerInput>,
@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui::{self, Color32, RichText, TextEdit},
+    EguiContexts,
+};
+use futures::channel::oneshot;
+
+use crate::{
+    game_util::{
+        events::PrizeUpdate,
+        resources::{NetworkStuff, PlayerName},
+        storage,
+    },
+    network::{
+        lnurl::{self, LightningAddress, LnurlError, PayParams},
+        messages::{ClientMessage, PrizeStatus},
+    },
+    GameStage,
+};
+
+use super::{player::Player, settings::Settings};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ClaimState {
+    Idle,
+    Resolving,
+    Ready { min_sats: u64, max_sats: u64 },
+    Claiming,
+    Paid(u64),
+    Failed(String),
+}
+
+#[derive(Resource)]
+pub struct PrizeClaim {
+    pub state: ClaimState,
+    pub resolving: Option<oneshot::Receiver<Result<PayParams, LnurlError>>>,
+}
+
+impl PrizeClaim {
+    pub fn new() -> Self {
+        Self {
+            state: ClaimState::Idle,
+            resolving: None,
+        }
+    }
+}
+
+pub struct PrizePlugin;
+
+impl Plugin for PrizePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PrizeClaim::new())
+            .add_event::<PrizeUpdate>()
+            .add_systems(Update, poll_prize_claim)
+            .add_systems(Update, prize_claim.run_if(in_state(GameStage::GameOver)))
+            .add_systems(OnEnter(GameStage::InGame), reset_prize_claim);
+    }
+}
+
+fn reset_prize_claim(mut prize_claim: ResMut<PrizeClaim>) {
+    *prize_claim = PrizeClaim::new();
+}
+
+fn poll_prize_claim(mut prize_claim: ResMut<PrizeClaim>, mut updates: EventReader<PrizeUpdate>) {
+    if let Some(result) = prize_claim.resolving.as_mut().map(|rx| rx.try_recv()) {
+        match result {
+            Ok(Some(Ok(params))) => {
+                prize_claim.state = ClaimState::Ready {
+                    min_sats: params.min_sats(),
+                    max_sats: params.max_sats(),
+                };
+                prize_claim.resolving = None;
+            }
+            Ok(Some(Err(e))) => {
+                prize_claim.state = ClaimState::Failed(e.to_string());
+                prize_claim.resolving = None;
+            }
+            Ok(None) => {}
+            Err(_) => {
+                prize_claim.state = ClaimState::Failed("lookup was cancelled".to_string());
+                prize_claim.resolving = None;
+            }
+        }
+    }
+
+    for update in updates.iter() {
+        prize_claim.state = match &update.0 {
+            PrizeStatus::Paid(sats) => ClaimState::Paid(*sats),
+            PrizeStatus::Rejected(reason) => ClaimState::Failed(reason.clone()),
+        };
+    }
+}
+
+pub fn ln_address_hint(ui: &mut egui::Ui, address: &str) {
+    if address.trim().is_empty() {
+        return;
+    }
+    if let Err(e) = LightningAddress::parse(address) {
+        ui.label(RichText::new(e.to_string()).color(Color32::LIGHT_RED));
+    }
+}
+
+fn prize_claim(
+    mut contexts: EguiContexts,
+    mut prize_claim: ResMut<PrizeClaim>,
+    mut player_name: ResMut<PlayerName>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    settings: Res<Settings>,
+    query_player: Query<&Player>,
+) {
+    if !query_player.iter().any(|player| player.won) {
+        return;
+    }
+
+    egui::Window::new("⚡ Claim your prize")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::Vec2::new(0.0, -20.0))
+        .show(contexts.ctx_mut(), |ui| {
+            let editable = matches!(
+                prize_claim.state,
+                ClaimState::Idle | ClaimState::Ready { .. } | ClaimState::Failed(_)
+            );
+
+            let response = ui.add_enabled(
+                editable,
+                TextEdit::singleline(&mut player_name.ln_address)
+                    .desired_width(200.0)
+                    .hint_text("you@wallet.com"),
+            );
+            if response.changed() {
+                prize_claim.state = ClaimState::Idle;
+            }
+            ln_address_hint(ui, &player_name.ln_address);
+
+            let address = LightningAddress::parse(&player_name.ln_address);
+
+            match prize_claim.state.clone() {
+                ClaimState::Idle | ClaimState::Failed(_) => {
+                    if let ClaimState::Failed(reason) = &prize_claim.state {
+                        ui.label(RichText::new(reason).color(Color32::LIGHT_RED));
+                    }
+                    if ui
+                        .add_enabled(address.is_ok(), egui::Button::new("Check address"))
+                        .clicked()
+                    {
+                        if let Ok(address) = &address {
+                            prize_claim.resolving =
+                                Some(lnurl::resolve(address, &settings.lnurl_endpoint));
+                            prize_claim.state = ClaimState::Resolving;
+                        }
+                    }
+                }
+                ClaimState::Resolving => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Looking up wallet…");
+                    });
+                }
+                ClaimState::Ready { min_sats, max_sats } => {
+                    ui.label(format!("Wallet accepts {} - {} sats", min_sats, max_sats));
+                    if ui.button("Claim").clicked() {
+                        if let Ok(address) = &address {
+                            storage::save("ln_address", &address.to_string());
+                            network_stuff.send(ClientMessage::ClaimPrize(address.to_string()));
+                            prize_claim.state = ClaimState::Claiming;
+                        }
+                    }
+                }
+                ClaimState::Claiming => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Paying out…");
+                    });
+                }
+                ClaimState::Paid(sats) => {
+                    ui.label(
+                        RichText::new(format!("⚡ {} sats sent!", sats)).color(Color32::YELLOW),
+                    );
+                }
+            }
+        });
+}
@@ -0,0 +1,238 @@
+use bevy::{prelude::*, utils::Instant};
+
+use bevy_egui::{
+    egui::{self, Color32, RichText, TextEdit},
+    EguiContexts,
+};
+use rand::Rng;
+
+use crate::{
+    game_util::{
+        events::ProfileUpdate,
+        resources::{NetworkStuff, PlayerName},
+        storage,
+    },
+    network::messages::{ClientMessage, GameMode, Profile, RunRecord},
+    GameStage,
+};
+
+use super::{modes::format_duration, settings::SettingsMenu};
+
+const HISTORY_SHOWN: usize = 20;
+
+#[derive(Resource)]
+pub struct Identity {
+    pub token: String,
+    pub profile: Option<Profile>,
+    pub received: Instant,
+    // another device's token pasted in to switch to it
+    pub restore: String,
+}
+
+impl Identity {
+    pub fn new() -> Self {
+        let token = storage::load("identity").unwrap_or_else(|| {
+            let token = Self::generate_token();
+            storage::save("identity", &token);
+            token
+        });
+
+        Self {
+            token,
+            profile: None,
+            received: Instant::now(),
+            restore: String::new(),
+        }
+    }
+
+    pub fn generate_token() -> String {
+        rand::thread_rng()
+            .gen::<[u8; 32]>()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    pub fn is_valid_token(token: &str) -> bool {
+        token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    pub fn server_now(&self) -> Option<u64> {
+        self.profile
+            .as_ref()
+            .map(|profile| profile.server_time + self.received.elapsed().as_secs())
+    }
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Identity::new())
+            .add_event::<ProfileUpdate>()
+            .add_systems(Update, (apply_profile_updates, remember_name))
+            .add_systems(OnEnter(GameStage::Profile), request_profile)
+            .add_systems(Update, profile_screen.run_if(in_state(GameStage::Profile)));
+    }
+}
+
+fn request_profile(mut network_stuff: ResMut<NetworkStuff>) {
+    network_stuff.send(ClientMessage::GetProfile);
+}
+
+fn apply_profile_updates(
+    mut identity: ResMut<Identity>,
+    mut player_name: ResMut<PlayerName>,
+    mut updates: EventReader<ProfileUpdate>,
+) {
+    for update in updates.iter() {
+        // a fresh device picks up the name the identity was last played with
+        if player_name.name.is_empty() && !update.0.name.is_empty() {
+            player_name.name = update.0.name.clone();
+        }
+        identity.profile = Some(update.0.clone());
+        identity.received = Instant::now();
+    }
+}
+
+// the name is kept once it's actually been played with, not on every keystroke
+// the menu takes the name mutably every frame, so compare against what was last written
+fn remember_name(player_name: Res<PlayerName>, mut saved: Local<Option<String>>) {
+    if player_name.submitted && saved.as_ref() != Some(&player_name.name) {
+        storage::save("name", &player_name.name);
+        *saved = Some(player_name.name.clone());
+    }
+}
+
+fn run_row(ui: &mut egui::Ui, run: &RunRecord, now: u64) {
+    ui.label(run.mode.title());
+    ui.label(run.mode.format_high_score(run.score));
+    let result = run.mode.result_text(run.score as usize, run.secs, run.won);
+    ui.label(if result.is_empty() {
+        run.mode.time_text(run.secs)
+    } else {
+        result
+    });
+    ui.label(
+        RichText::new(format!(
+            "{} ago",
+            format_duration(now.saturating_sub(run.played_at) as i64)
+        ))
+        .color(Color32::GRAY),
+    );
+    ui.end_row();
+}
+
+fn profile_screen(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameStage>>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut identity: ResMut<Identity>,
+    player_name: Res<PlayerName>,
+    settings_menu: Res<SettingsMenu>,
+) {
+    if settings_menu.open {
+        return;
+    }
+
+    let mut switch_to = None;
+
+    egui::Window::new("👤 Profile")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            let now = identity.server_now().unwrap_or(0);
+
+            match identity.profile.as_ref() {
+                Some(profile) => {
+                    let name = if player_name.name.is_empty() {
+                        &profile.name
+                    } else {
+                        &player_name.name
+                    };
+                    ui.heading(name);
+                    ui.label(format!(
+                        "Playing for {}",
+                        format_duration(now.saturating_sub(profile.created_at) as i64)
+                    ));
+
+                    ui.separator();
+                    ui.label("Personal bests");
+                    egui::Grid::new("personal_bests")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for mode in GameMode::ALL {
+                                ui.label(mode.title());
+                                match profile.personal_bests.iter().find(|(m, _)| *m == mode) {
+                                    Some((_, best)) => ui.label(mode.format_high_score(*best)),
+                                    None => ui.label("-"),
+                                };
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.separator();
+                    ui.label("Recent runs");
+                    egui::ScrollArea::vertical()
+                        .max_height(220.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("run_history").striped(true).show(ui, |ui| {
+                                for run in profile.history.iter().take(HISTORY_SHOWN) {
+                                    run_row(ui, run, now);
+                                }
+                            });
+                            if profile.history.is_empty() {
+                                ui.label("No runs yet");
+                            }
+                        });
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+
+            ui.separator();
+            ui.collapsing("Use on another device", |ui| {
+                ui.label("This token is your account, keep it private.");
+                ui.horizontal(|ui| {
+                    let short: String = identity.token.chars().take(8).collect();
+                    ui.label(RichText::new(format!("{}…", short)).monospace());
+                    if ui.button("Copy token").clicked() {
+                        ui.output_mut(|o| o.copied_text = identity.token.clone());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut identity.restore)
+                            .desired_width(200.0)
+                            .hint_text("paste a token"),
+                    );
+                    let restore = identity.restore.trim().to_lowercase();
+                    if ui
+                        .add_enabled(
+                            Identity::is_valid_token(&restore) && restore != identity.token,
+                            egui::Button::new("Switch"),
+                        )
+                        .clicked()
+                    {
+                        switch_to = Some(restore);
+                    }
+                });
+            });
+
+            ui.separator();
+            if ui.button("Back").clicked() {
+                next_state.set(GameStage::Menu);
+            }
+        });
+
+    if let Some(token) = switch_to {
+        storage::save("identity", &token);
+        identity.token = token.clone();
+        identity.restore.clear();
+        identity.profile = None;
+        network_stuff.send(ClientMessage::Identify(token));
+        network_stuff.send(ClientMessage::GetProfile);
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::game_util::resources::{ClientTick, Objects};
+
+use super::player::{Enemy, Player};
+
+// umbrellas closer than this are overlapping
+pub const BUMP_DISTANCE: f32 = 20.0;
+
+// each umbrella takes half of the overlap, the server pushes the other half
+pub fn bump_offset(pos: Vec2, other: Vec2) -> Vec2 {
+    let offset = pos - other;
+    let distance = offset.length();
+
+    if distance >= BUMP_DISTANCE {
+        return Vec2::ZERO;
+    }
+
+    let direction = if distance > f32::EPSILON {
+        offset / distance
+    } else {
+        Vec2::Y
+    };
+
+    direction * (BUMP_DISTANCE - distance) / 2.0
+}
+
+pub fn player_bumps(
+    objects: Res<Objects>,
+    mut query_player: Query<(&mut Transform, &mut Player)>,
+    query_enemy: Query<(&Transform, &Enemy), Without<Player>>,
+    client_tick: Res<ClientTick>,
+) {
+    if !objects.pvp || client_tick.pause != 0 {
+        return;
+    }
+    let Some(tick) = client_tick.tick else {
+        return;
+    };
+
+    for (mut t, mut player) in query_player.iter_mut() {
+        let mut push = Vec2::ZERO;
+
+        for (enemy_t, enemy) in query_enemy.iter() {
+            if enemy.alive {
+                push += bump_offset(t.translation.truncate(), enemy_t.translation.truncate());
+            }
+        }
+
+        if push != Vec2::ZERO {
+            player.apply_bump(&mut t, push, tick);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apart_umbrellas_do_not_bump() {
+        assert_eq!(
+            bump_offset(Vec2::ZERO, Vec2::new(BUMP_DISTANCE, 0.0)),
+            Vec2::ZERO
+        );
+        assert_eq!(bump_offset(Vec2::ZERO, Vec2::new(30.0, 40.0)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn overlap_is_split_in_half() {
+        let push = bump_offset(Vec2::new(5.0, 0.0), Vec2::ZERO);
+        assert!((push - Vec2::new((BUMP_DISTANCE - 5.0) / 2.0, 0.0)).length() < 1e-5);
+
+        // the other umbrella gets pushed the opposite way
+        let other = bump_offset(Vec2::ZERO, Vec2::new(5.0, 0.0));
+        assert!((push + other).length() < 1e-5);
+    }
+
+    #[test]
+    fn stacked_umbrellas_push_up() {
+        assert_eq!(
+            bump_offset(Vec2::ONE, Vec2::ONE),
+            Vec2::Y * BUMP_DISTANCE / 2.0
+        );
+    }
+}
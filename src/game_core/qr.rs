@@ -0,0 +1,94 @@
+use bevy::{prelude::Resource, utils::HashMap};
+use bevy_egui::egui::{self, Color32, ColorImage, TextureOptions};
+use qrcode::{Color, QrCode};
+
+// modules of white border scanners need around the code
+const QUIET_ZONE: usize = 4;
+const MAX_CACHED: usize = 16;
+
+#[derive(Resource)]
+pub struct QrCodes(pub HashMap<String, egui::TextureHandle>);
+
+impl QrCodes {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+fn render(data: &str) -> Option<ColorImage> {
+    let code = QrCode::new(data.as_bytes()).ok()?;
+    let width = code.width();
+    let side = width + QUIET_ZONE * 2;
+    let colors = code.to_colors();
+
+    let mut pixels = vec![Color32::WHITE; side * side];
+    for y in 0..width {
+        for x in 0..width {
+            if colors[y * width + x] == Color::Dark {
+                pixels[(y + QUIET_ZONE) * side + x + QUIET_ZONE] = Color32::BLACK;
+            }
+        }
+    }
+
+    Some(ColorImage {
+        size: [side, side],
+        pixels,
+    })
+}
+
+// textures are cached by their contents so a code is only built once
+pub fn qr_code(ui: &mut egui::Ui, codes: &mut QrCodes, data: &str, size: f32) -> egui::Response {
+    if !codes.0.contains_key(data) {
+        let Some(image) = render(data) else {
+            return ui.label("couldn't make a QR code for this");
+        };
+        if codes.0.len() >= MAX_CACHED {
+            codes.0.clear();
+        }
+        let texture = ui
+            .ctx()
+            .load_texture(format!("qr:{}", data), image, TextureOptions::NEAREST);
+        codes.0.insert(data.to_string(), texture);
+    }
+
+    let texture = &codes.0[data];
+    ui.image(texture.id(), egui::vec2(size, size))
+}
+
+// the page without its query, so a game served from a sub path links back to itself
+#[cfg(target_arch = "wasm32")]
+fn page_url() -> Option<String> {
+    let location = web_sys::window()?.location();
+    Some(format!(
+        "{}{}",
+        location.origin().ok()?,
+        location.pathname().ok()?
+    ))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn page_url() -> Option<String> {
+    None
+}
+
+pub fn room_invite_link(code: &str) -> String {
+    let page = page_url().unwrap_or_else(|| "https://satrunner.gg/".to_string());
+    format!("{}?room={}", page, code)
+}
+
+// a room code from an invite link the page was opened with
+#[cfg(target_arch = "wasm32")]
+pub fn invite_from_url() -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("room="))
+        .filter(|code| !code.is_empty())
+        .map(|code| code.to_uppercase())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn invite_from_url() -> Option<String> {
+    None
+}
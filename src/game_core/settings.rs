@@ -0,0 +1,534 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+use bevy_egui::{
+    egui::{self, Color32},
+    EguiContexts, EguiSettings,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_util::{
+        components::{NamePlates, NamePlatesLocal},
+        resources::{ClientTick, NetworkStuff, Objects},
+        storage,
+    },
+    GameStage,
+};
+
+use super::{
+    audio::AudioSettings,
+    lobby::Rooms,
+    nostr::{nostr_settings, Nostr},
+    player::{Enemy, Player},
+};
+
+const UI_SCALES: [f32; 4] = [0.75, 1.0, 1.25, 1.5];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ControlScheme {
+    #[default]
+    Tap,
+    Drag,
+    Joystick,
+    Keyboard,
+}
+
+impl ControlScheme {
+    pub const ALL: [ControlScheme; 4] = [
+        ControlScheme::Tap,
+        ControlScheme::Drag,
+        ControlScheme::Joystick,
+        ControlScheme::Keyboard,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ControlScheme::Tap => "Tap to move",
+            ControlScheme::Drag => "Hold to steer",
+            ControlScheme::Joystick => "Joystick",
+            ControlScheme::Keyboard => "Keyboard",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MoveAction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl MoveAction {
+    pub const ALL: [MoveAction; 4] = [
+        MoveAction::Up,
+        MoveAction::Down,
+        MoveAction::Left,
+        MoveAction::Right,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            MoveAction::Up => "Up",
+            MoveAction::Down => "Down",
+            MoveAction::Left => "Left",
+            MoveAction::Right => "Right",
+        }
+    }
+
+    pub fn direction(&self) -> Vec2 {
+        match self {
+            MoveAction::Up => Vec2::Y,
+            MoveAction::Down => Vec2::NEG_Y,
+            MoveAction::Left => Vec2::NEG_X,
+            MoveAction::Right => Vec2::X,
+        }
+    }
+}
+
+// two keys per action so WASD and the arrows both work out of the box
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct KeyBindings {
+    pub up: [KeyCode; 2],
+    pub down: [KeyCode; 2],
+    pub left: [KeyCode; 2],
+    pub right: [KeyCode; 2],
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            up: [KeyCode::W, KeyCode::Up],
+            down: [KeyCode::S, KeyCode::Down],
+            left: [KeyCode::A, KeyCode::Left],
+            right: [KeyCode::D, KeyCode::Right],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: MoveAction) -> &[KeyCode; 2] {
+        match action {
+            MoveAction::Up => &self.up,
+            MoveAction::Down => &self.down,
+            MoveAction::Left => &self.left,
+            MoveAction::Right => &self.right,
+        }
+    }
+
+    pub fn get_mut(&mut self, action: MoveAction) -> &mut [KeyCode; 2] {
+        match action {
+            MoveAction::Up => &mut self.up,
+            MoveAction::Down => &mut self.down,
+            MoveAction::Left => &mut self.left,
+            MoveAction::Right => &mut self.right,
+        }
+    }
+
+    pub fn direction(&self, keys: &Input<KeyCode>) -> Vec2 {
+        MoveAction::ALL
+            .iter()
+            .filter(|action| self.get(**action).iter().any(|key| keys.pressed(*key)))
+            .map(|action| action.direction())
+            .sum::<Vec2>()
+            .normalize_or_zero()
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub name_plates: bool,
+    pub show_enemies: bool,
+    pub ui_scale: f32,
+    pub controls: ControlScheme,
+    pub diagnostics: bool,
+    pub server: String,
+    pub keys: KeyBindings,
+    // empty resolves lightning addresses at their own domain
+    pub lnurl_endpoint: String,
+    // empty keeps signed scores between the client and the game server
+    pub nostr_relay: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            name_plates: true,
+            show_enemies: true,
+            ui_scale: 1.0,
+            controls: ControlScheme::Tap,
+            diagnostics: false,
+            server: Settings::DEFAULT_SERVER.to_string(),
+            keys: KeyBindings::default(),
+            lnurl_endpoint: String::new(),
+            nostr_relay: Settings::DEFAULT_RELAY.to_string(),
+        }
+    }
+}
+
+impl Settings {
+    #[cfg(debug_assertions)]
+    pub const DEFAULT_SERVER: &'static str = "ws://0.0.0.0:3030/run";
+    #[cfg(not(debug_assertions))]
+    pub const DEFAULT_SERVER: &'static str = "wss://satrunner.gg/run";
+    #[cfg(debug_assertions)]
+    pub const DEFAULT_RELAY: &'static str = "ws://localhost:7777";
+    #[cfg(not(debug_assertions))]
+    pub const DEFAULT_RELAY: &'static str = "wss://nos.lol";
+
+    pub fn new() -> Self {
+        storage::load("settings").unwrap_or_default()
+    }
+}
+
+#[derive(Resource)]
+pub struct SettingsMenu {
+    pub open: bool,
+    // endpoint being edited, only saved once it looks like a websocket url
+    pub server: Option<String>,
+    // action and slot waiting for the next key press
+    pub rebinding: Option<(MoveAction, usize)>,
+}
+
+impl SettingsMenu {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            server: None,
+            rebinding: None,
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::new())
+            .insert_resource(SettingsMenu::new())
+            .add_plugins(FrameTimeDiagnosticsPlugin)
+            .add_systems(
+                Update,
+                (
+                    settings_keys,
+                    settings_window,
+                    apply_settings,
+                    diagnostics_overlay,
+                    save_settings,
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(GameStage::InGame), close_settings)
+            .add_systems(OnExit(GameStage::InGame), close_settings);
+    }
+}
+
+fn settings_keys(
+    keys: Res<Input<KeyCode>>,
+    game_stage: Res<State<GameStage>>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut settings: ResMut<Settings>,
+) {
+    if let Some((action, slot)) = settings_menu.rebinding {
+        // escape cancels instead of being bound
+        if keys.just_pressed(KeyCode::Escape) {
+            settings_menu.rebinding = None;
+        } else if let Some(key) = keys.get_just_pressed().next() {
+            settings.keys.get_mut(action)[slot] = *key;
+            settings_menu.rebinding = None;
+        }
+        return;
+    }
+
+    if *game_stage.get() == GameStage::InGame && keys.just_pressed(KeyCode::Escape) {
+        settings_menu.open = !settings_menu.open;
+        settings_menu.server = None;
+    }
+}
+
+fn close_settings(mut settings_menu: ResMut<SettingsMenu>) {
+    settings_menu.open = false;
+    settings_menu.server = None;
+    settings_menu.rebinding = None;
+}
+
+fn settings_window(
+    mut contexts: EguiContexts,
+    game_stage: Res<State<GameStage>>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut settings: ResMut<Settings>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut nostr: ResMut<Nostr>,
+    mut network_stuff: ResMut<NetworkStuff>,
+) {
+    let in_game = *game_stage.get() == GameStage::InGame;
+    let ctx = contexts.ctx_mut();
+
+    if in_game && !settings_menu.open {
+        egui::Area::new("pause_button")
+            .anchor(egui::Align2::LEFT_BOTTOM, egui::Vec2::new(10.0, -10.0))
+            .show(ctx, |ui| {
+                if ui.button("⏸").clicked() {
+                    settings_menu.open = true;
+                }
+            });
+    }
+
+    if !settings_menu.open {
+        return;
+    }
+
+    if in_game {
+        // the run keeps going on the server, this only dims the field
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Background,
+            egui::Id::new("pause_overlay"),
+        ));
+        painter.rect_filled(
+            ctx.screen_rect(),
+            0.0,
+            Color32::from_rgba_unmultiplied(0, 0, 0, 140),
+        );
+    }
+
+    let title = if in_game {
+        "⏸ Paused"
+    } else {
+        "⚙ Settings"
+    };
+    let mut new_settings = settings.clone();
+    let mut new_audio = audio_settings.clone();
+    let mut close = false;
+
+    egui::Window::new(title)
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            if in_game {
+                ui.label("The rain doesn't stop while you're here.");
+                ui.separator();
+            }
+
+            egui::Grid::new("settings_grid")
+                .num_columns(2)
+                .spacing([20.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("Sound");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut new_audio.muted, "🔇");
+                        ui.add_enabled(
+                            !new_audio.muted,
+                            egui::Slider::new(&mut new_audio.volume, 0.0..=1.0).text("🔊"),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label("Name plates");
+                    ui.checkbox(&mut new_settings.name_plates, "");
+                    ui.end_row();
+
+                    ui.label("Show other players");
+                    ui.checkbox(&mut new_settings.show_enemies, "");
+                    ui.end_row();
+
+                    ui.label("UI scale");
+                    ui.horizontal(|ui| {
+                        for scale in UI_SCALES {
+                            ui.selectable_value(
+                                &mut new_settings.ui_scale,
+                                scale,
+                                format!("{}%", (scale * 100.0) as u32),
+                            );
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Controls");
+                    ui.horizontal(|ui| {
+                        for scheme in ControlScheme::ALL {
+                            ui.selectable_value(&mut new_settings.controls, scheme, scheme.title());
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Diagnostics");
+                    ui.checkbox(&mut new_settings.diagnostics, "");
+                    ui.end_row();
+
+                    ui.label("Server");
+                    ui.horizontal(|ui| {
+                        let server = settings_menu
+                            .server
+                            .get_or_insert_with(|| new_settings.server.clone());
+                        ui.text_edit_singleline(server);
+                        let valid = server.starts_with("ws://") || server.starts_with("wss://");
+                        if ui.add_enabled(valid, egui::Button::new("Save")).clicked() {
+                            new_settings.server = server.trim().to_string();
+                        }
+                        if ui.button("Reset").clicked() {
+                            *server = Settings::DEFAULT_SERVER.to_string();
+                            new_settings.server = Settings::DEFAULT_SERVER.to_string();
+                        }
+                    });
+                    ui.end_row();
+                });
+
+            ui.collapsing("Lightning", |ui| {
+                ui.label("LNURL endpoint, leave empty to use the address' own domain");
+                ui.add(
+                    egui::TextEdit::singleline(&mut new_settings.lnurl_endpoint)
+                        .hint_text("http://localhost:8080"),
+                );
+            });
+
+            ui.collapsing("Nostr", |ui| {
+                nostr_settings(ui, &mut nostr, &mut network_stuff, &mut new_settings);
+            });
+
+            ui.collapsing("Key bindings", |ui| {
+                egui::Grid::new("key_bindings_grid")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for action in MoveAction::ALL {
+                            ui.label(action.title());
+                            for (slot, key) in new_settings.keys.get(action).iter().enumerate() {
+                                let text = if settings_menu.rebinding == Some((action, slot)) {
+                                    "press a key…".to_string()
+                                } else {
+                                    format!("{:?}", key)
+                                };
+                                if ui.button(text).clicked() {
+                                    settings_menu.rebinding = Some((action, slot));
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if ui.button("Reset keys").clicked() {
+                    new_settings.keys = KeyBindings::default();
+                    settings_menu.rebinding = None;
+                }
+            });
+
+            ui.label(
+                egui::RichText::new("Server changes apply after reloading the game.")
+                    .color(Color32::GRAY),
+            );
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let label = if in_game { "Resume" } else { "Close" };
+                if ui.button(label).clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    settings.set_if_neq(new_settings);
+    audio_settings.set_if_neq(new_audio);
+
+    if close {
+        settings_menu.open = false;
+        settings_menu.server = None;
+        settings_menu.rebinding = None;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_settings(
+    settings: Res<Settings>,
+    mut egui_settings: ResMut<EguiSettings>,
+    mut query_enemy: Query<&mut Visibility, With<Enemy>>,
+    mut query_plates: Query<
+        &mut Visibility,
+        (
+            Or<(With<NamePlates>, With<NamePlatesLocal>)>,
+            Without<Enemy>,
+        ),
+    >,
+) {
+    if (egui_settings.scale_factor - settings.ui_scale as f64).abs() > f64::EPSILON {
+        egui_settings.scale_factor = settings.ui_scale as f64;
+    }
+
+    // enemies and name plates spawn at any time so this runs every frame
+    let enemy_visibility = if settings.show_enemies {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in query_enemy.iter_mut() {
+        visibility.set_if_neq(enemy_visibility);
+    }
+
+    let plate_visibility = if settings.name_plates {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in query_plates.iter_mut() {
+        visibility.set_if_neq(plate_visibility);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn diagnostics_overlay(
+    mut contexts: EguiContexts,
+    settings: Res<Settings>,
+    diagnostics: Res<DiagnosticsStore>,
+    client_tick: Res<ClientTick>,
+    objects: Res<Objects>,
+    rooms: Res<Rooms>,
+    query_player: Query<&Player>,
+    query_enemy: Query<&Enemy>,
+) {
+    if !settings.diagnostics {
+        return;
+    }
+
+    let fps = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    let frame_time = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|frame_time| frame_time.smoothed())
+        .unwrap_or(0.0);
+    let pending = query_player
+        .iter()
+        .map(|player| player.pending_inputs.len())
+        .sum::<usize>();
+
+    egui::Area::new("diagnostics")
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::Vec2::new(10.0, -40.0))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let text = format!(
+                "fps {:.0} ({:.1} ms)\ntick {} pause {}\npending inputs {}\nrain {} bolts {}\nplayers {}\nping {}",
+                fps,
+                frame_time,
+                client_tick.tick.unwrap_or(0),
+                client_tick.pause,
+                pending,
+                objects.rain_pos.len(),
+                objects.bolt_pos.len(),
+                query_enemy.iter().count() + 1,
+                rooms
+                    .ping_ms
+                    .map(|ms| format!("{} ms", ms))
+                    .unwrap_or_else(|| "-".to_string()),
+            );
+            ui.label(egui::RichText::new(text).monospace().color(Color32::LIGHT_GRAY));
+        });
+}
+
+fn save_settings(settings: Res<Settings>) {
+    if settings.is_changed() && !settings.is_added() {
+        storage::save("settings", &*settings);
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use bevy_egui::{egui, EguiContexts};
+use uuid::Uuid;
+
+use crate::{
+    game_util::resources::NetworkStuff, network::messages::ClientMessage, GameStage, KeyboardState,
+};
+
+use super::player::Enemy;
+
+#[derive(Resource)]
+pub struct Spectate {
+    pub target: Option<Uuid>,
+    pub overview: bool,
+    pub from_menu: bool,
+}
+
+impl Spectate {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            overview: false,
+            from_menu: false,
+        }
+    }
+}
+
+pub fn spectate(
+    mut contexts: EguiContexts,
+    mut spectate: ResMut<Spectate>,
+    mut next_state: ResMut<NextState<GameStage>>,
+    keys: Res<Input<KeyCode>>,
+    query_enemy: Query<&Enemy>,
+) {
+    let mut enemies: Vec<_> = query_enemy
+        .iter()
+        .filter(|enemy| enemy.alive && !enemy.name.is_empty())
+        .map(|enemy| (enemy.id, enemy.name.clone()))
+        .collect();
+    enemies.sort_by_key(|(id, _)| *id);
+
+    let mut step = 0;
+    if keys.just_pressed(KeyCode::Left) || keys.just_pressed(KeyCode::A) {
+        step -= 1;
+    }
+    if keys.just_pressed(KeyCode::Right)
+        || keys.just_pressed(KeyCode::D)
+        || keys.just_pressed(KeyCode::Tab)
+    {
+        step += 1;
+    }
+    if keys.just_pressed(KeyCode::Space) {
+        spectate.overview = !spectate.overview;
+    }
+    let mut leave = keys.just_pressed(KeyCode::Escape);
+
+    let index = spectate
+        .target
+        .and_then(|target| enemies.iter().position(|(id, _)| *id == target));
+
+    let ctx = contexts.ctx_mut();
+
+    egui::Area::new("spectate")
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::Vec2::new(0.0, -20.0))
+        .show(ctx, |ui| {
+            let watching = match index {
+                Some(index) if !spectate.overview => enemies[index].1.clone(),
+                _ if spectate.overview || enemies.is_empty() => "the whole field".to_string(),
+                _ => enemies[0].1.clone(),
+            };
+            ui.label(format!("Spectating {}", watching));
+            ui.horizontal(|ui| {
+                if ui.button("◀").clicked() {
+                    step -= 1;
+                }
+                if ui.button("Overview").clicked() {
+                    spectate.overview = !spectate.overview;
+                }
+                if ui.button("▶").clicked() {
+                    step += 1;
+                }
+                if ui.button("Back").clicked() {
+                    leave = true;
+                }
+            });
+        });
+
+    if !enemies.is_empty() {
+        let len = enemies.len() as isize;
+        let current = index.map(|index| index as isize).unwrap_or(0);
+        let next = (current + step).rem_euclid(len) as usize;
+        spectate.target = Some(enemies[next].0);
+        if step != 0 {
+            spectate.overview = false;
+        }
+    } else {
+        spectate.target = None;
+    }
+
+    if leave {
+        if spectate.from_menu {
+            next_state.set(GameStage::Menu);
+        } else {
+            next_state.set(GameStage::GameOver);
+        }
+    }
+}
+
+pub fn stop_spectating(
+    mut spectate: ResMut<Spectate>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut keyboard_state: ResMut<NextState<KeyboardState>>,
+) {
+    if spectate.from_menu {
+        network_stuff.send(ClientMessage::Spectate(false));
+        keyboard_state.set(KeyboardState::On);
+    }
+
+    *spectate = Spectate::new();
+}
@@ -15,6 +15,7 @@ use crate::{
         resources::{BoltPool, RainPool},
     },
     keyboard::components::KeyboardNode,
+    network::messages::GameMode,
     GameStage, KeyboardState,
 };
 
@@ -24,6 +25,7 @@ const FONT_SIZE: f32 = 15.0;
 
 const PLAYER_SIZE: Vec2 = Vec2::new(20.0, 20.0);
 const DOTS_SIZE: Vec2 = Vec2::new(10., 10.);
+const RAIN_STREAK_SIZE: Vec2 = Vec2::new(2., 18.);
 const LN_SIZE: Vec2 = Vec2::new(10., 10.);
 
 pub fn spawn_player(
@@ -48,29 +50,6 @@ pub fn spawn_player(
                 },
                 KeyboardNode,
             ));
-
-            //     commands
-            //         .spawn(
-            //             VirtualJoystickBundle::new(VirtualJoystickNode {
-            //                 border_image: asset_server.load("Outline.png"),
-            //                 knob_image: asset_server.load("Knob.png"),
-            //                 knob_size: Vec2::new(40., 40.),
-            //                 dead_zone: 0.,
-            //                 id: "UniqueJoystick".to_string(),
-            //                 axis: VirtualJoystickAxis::Both,
-            //                 behaviour: VirtualJoystickType::Floating,
-            //             })
-            //             .set_color(TintColor(Color::WHITE.with_a(0.2)))
-            //             .set_style(Style {
-            //                 width: Val::Px(75.),
-            //                 height: Val::Px(75.),
-            //                 position_type: PositionType::Absolute,
-            //                 right: Val::Percent(8.),
-            //                 bottom: Val::Percent(8.),
-            //                 ..default()
-            //             }),
-            //         )
-            //         .insert(VirtualJoystickInteractionArea);
         }
     }
 
@@ -96,23 +75,18 @@ pub fn spawn_player(
             ..Default::default()
         })
         .insert(Player {
-            last_direction: None,
             id: *id,
             target: Vec2::ZERO,
             score: 0,
             pending_inputs: Vec::new(),
+            bumps: HashMap::new(),
             name: String::new(),
             spawn_time: None,
             death_time: None,
+            won: false,
+            team: None,
         })
         .with_children(|parent| {
-            parent.spawn(Camera2dBundle {
-                transform: Transform::from_translation(Vec3::new(0., 0., 1.0)),
-                projection: OrthographicProjection {
-                    ..Default::default()
-                },
-                ..Default::default()
-            });
             parent
                 .spawn(Text2dBundle {
                     text: text.with_alignment(TextAlignment::Center),
@@ -139,6 +113,8 @@ pub fn spawn_enemies(
     enemy_name: Option<String>,
     asset_server: &Res<AssetServer>,
     spawn_time: u64,
+    mode: GameMode,
+    team: Option<u8>,
 ) {
     let target = target.unwrap_or([0.0, 0.0]);
     let player_pos = player_pos.unwrap_or([0.0, 0.0]);
@@ -180,6 +156,9 @@ pub fn spawn_enemies(
                 },
                 score,
                 name: enemy_name,
+                mode,
+                team,
+                alive: true,
                 spawn_time: stopwatch,
                 pending_inputs: VecDeque::new(),
                 past_pos: HashMap::new(),
@@ -199,6 +178,33 @@ pub fn spawn_enemies(
     }
 }
 
+pub const JOYSTICK_ID: &str = "UniqueJoystick";
+
+pub fn spawn_joystick(commands: &mut Commands, asset_server: &Res<AssetServer>) {
+    commands
+        .spawn(
+            VirtualJoystickBundle::new(VirtualJoystickNode {
+                border_image: asset_server.load("Outline.png"),
+                knob_image: asset_server.load("Knob.png"),
+                knob_size: Vec2::new(40., 40.),
+                dead_zone: 0.,
+                id: JOYSTICK_ID.to_string(),
+                axis: VirtualJoystickAxis::Both,
+                behaviour: VirtualJoystickType::Floating,
+            })
+            .set_color(TintColor(Color::WHITE.with_a(0.2)))
+            .set_style(Style {
+                width: Val::Px(120.),
+                height: Val::Px(120.),
+                position_type: PositionType::Absolute,
+                right: Val::Percent(8.),
+                bottom: Val::Percent(8.),
+                ..default()
+            }),
+        )
+        .insert(VirtualJoystickInteractionArea);
+}
+
 pub fn pool_rain(
     mut commands: Commands,
     mut rain_pool: ResMut<RainPool>,
@@ -218,6 +224,18 @@ pub fn pool_rain(
             })
             .insert(Rain)
             .insert(Visibility::Hidden)
+            .with_children(|parent| {
+                // faint streak trailing above the droplet
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(0.8, 0.9, 1.0, 0.25),
+                        custom_size: Some(RAIN_STREAK_SIZE),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(0., RAIN_STREAK_SIZE.y / 2.0 + 3.0, -0.1),
+                    ..Default::default()
+                });
+            })
             .id();
         rain_pool.0.push_back(rain);
     }
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContexts,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_util::{events::RunFinished, storage},
+    network::messages::GameMode,
+    GameStage,
+};
+
+use super::{
+    modes::{format_duration, format_secs, SelectedMode},
+    player::{Player, PLAYER_SPEED},
+    settings::SettingsMenu,
+};
+
+// the furthest the umbrella walks in one tick (falling is twice as fast), plus float slack
+const MAX_TICK_STEP: f32 = PLAYER_SPEED * 2.0 + 0.1;
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Stats {
+    pub runs: u64,
+    pub bolts: u64,
+    // fastest 21 bolt run in seconds
+    pub best_bolt_time: Option<u64>,
+    pub longest_survival: u64,
+    pub rain_deaths: u64,
+    pub secs_played: u64,
+    // world units the umbrella moved
+    pub distance: f32,
+    // the run in progress or just finished, not saved
+    #[serde(skip)]
+    pub run_distance: f32,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        storage::load("stats").unwrap_or_default()
+    }
+
+    pub fn bolts_per_minute(&self) -> f32 {
+        if self.secs_played == 0 {
+            0.0
+        } else {
+            self.bolts as f32 * 60.0 / self.secs_played as f32
+        }
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Stats::new())
+            .add_event::<RunFinished>()
+            .add_systems(Update, record_run)
+            .add_systems(OnEnter(GameStage::InGame), reset_run_distance)
+            .add_systems(
+                FixedUpdate,
+                track_distance.run_if(in_state(GameStage::InGame)),
+            )
+            .add_systems(Update, stats_screen.run_if(in_state(GameStage::Stats)));
+    }
+}
+
+fn reset_run_distance(mut stats: ResMut<Stats>) {
+    stats.run_distance = 0.0;
+}
+
+// measured once a tick from the predicted position, a step longer than a tick can walk is a
+// server correction or a pvp push and isn't counted; corrections smaller than that still are
+fn track_distance(
+    mut stats: ResMut<Stats>,
+    query_player: Query<(&Transform, &Player)>,
+    mut last_pos: Local<Option<Vec2>>,
+) {
+    for (t, player) in query_player.iter() {
+        let pos = t.translation.truncate();
+        // the player is moved back to the centre when a run ends
+        if player.death_time.is_some() {
+            *last_pos = None;
+            continue;
+        }
+        if let Some(last) = *last_pos {
+            let step = pos.distance(last);
+            if step > 0.0 && step <= MAX_TICK_STEP {
+                stats.run_distance += step;
+                stats.distance += step;
+            }
+        }
+        *last_pos = Some(pos);
+    }
+}
+
+fn record_run(
+    mut stats: ResMut<Stats>,
+    mut runs: EventReader<RunFinished>,
+    selected_mode: Res<SelectedMode>,
+) {
+    for run in runs.iter() {
+        let mode = selected_mode.mode;
+
+        stats.runs += 1;
+        stats.bolts += run.score as u64;
+        stats.secs_played += run.secs;
+        stats.longest_survival = stats.longest_survival.max(run.secs);
+        if run.by_rain {
+            stats.rain_deaths += 1;
+        }
+        if mode == GameMode::TimeAttack && mode.is_won(run.score, run.won) {
+            stats.best_bolt_time = Some(
+                stats
+                    .best_bolt_time
+                    .map_or(run.secs, |best| best.min(run.secs)),
+            );
+        }
+
+        storage::save("stats", &*stats);
+    }
+}
+
+fn stats_grid(ui: &mut egui::Ui, stats: &Stats) {
+    egui::Grid::new("lifetime_stats")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            let rows = [
+                ("Runs played", stats.runs.to_string()),
+                ("Bolts collected", format!("{}⚡", stats.bolts)),
+                (
+                    "Best 21 bolt time",
+                    stats
+                        .best_bolt_time
+                        .map(format_secs)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                ("Longest survival", format_secs(stats.longest_survival)),
+                ("Caught by rain", stats.rain_deaths.to_string()),
+                (
+                    "Bolts per minute",
+                    format!("{:.1}", stats.bolts_per_minute()),
+                ),
+                ("Time played", format_duration(stats.secs_played as i64)),
+                ("Distance", format!("{:.0}", stats.distance)),
+            ];
+            for (label, value) in rows {
+                ui.label(label);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+}
+
+// a few lines for the game over panel
+pub fn run_summary(ui: &mut egui::Ui, stats: &Stats) {
+    ui.label(format!("Umbrella travelled {:.0}", stats.run_distance));
+    ui.label(
+        RichText::new(format!(
+            "{} runs, {}⚡ lifetime, {:.1} bolts/min",
+            stats.runs,
+            stats.bolts,
+            stats.bolts_per_minute()
+        ))
+        .color(Color32::GRAY),
+    );
+}
+
+fn stats_screen(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameStage>>,
+    stats: Res<Stats>,
+    settings_menu: Res<SettingsMenu>,
+) {
+    if settings_menu.open {
+        return;
+    }
+
+    egui::Window::new("📊 Stats")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            if stats.runs == 0 {
+                ui.label("Play a run to start collecting stats");
+            }
+            stats_grid(ui, &stats);
+
+            ui.separator();
+            if ui.button("Back").clicked() {
+                next_state.set(GameStage::Menu);
+            }
+        });
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+use bevy_egui::egui::Color32;
+
+pub const TEAM_COUNT: u8 = 2;
+
+const TEAM_COLORS: [Color; TEAM_COUNT as usize] = [Color::TOMATO, Color::TURQUOISE];
+const TEAM_NAMES: [&str; TEAM_COUNT as usize] = ["Red", "Blue"];
+
+pub fn team_color(team: u8) -> Color {
+    TEAM_COLORS[team as usize % TEAM_COLORS.len()]
+}
+
+pub fn team_color32(team: u8) -> Color32 {
+    let [r, g, b, _] = team_color(team).as_rgba_u8();
+    Color32::from_rgb(r, g, b)
+}
+
+pub fn team_name(team: u8) -> &'static str {
+    TEAM_NAMES[team as usize % TEAM_NAMES.len()]
+}
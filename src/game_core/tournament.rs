@@ -0,0 +1,356 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, Instant},
+};
+
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContexts,
+};
+use satrunner_payout::tournament::split_pot;
+
+use crate::{
+    game_util::{
+        events::TournamentUpdate,
+        resources::{FixedRun, NetworkStuff, PendingRun, PlayerName},
+    },
+    network::messages::{
+        ClientMessage, Tournament, TournamentInvoice, TournamentMessage, TournamentStatus,
+    },
+    GameStage, KeyboardState,
+};
+
+use super::{
+    modes::{format_duration, SelectedMode},
+    player::Player,
+    qr::{qr_code, QrCodes},
+    settings::SettingsMenu,
+};
+
+#[derive(Resource)]
+pub struct Tournaments {
+    pub list: Vec<Tournament>,
+    pub received: Instant,
+    pub invoice: Option<TournamentInvoice>,
+    pub invoice_received: Instant,
+    pub standings: HashMap<u32, Vec<(String, u64)>>,
+    pub selected: Option<u32>,
+    pub error: Option<String>,
+}
+
+impl Tournaments {
+    pub fn new() -> Self {
+        Self {
+            list: Vec::new(),
+            received: Instant::now(),
+            invoice: None,
+            invoice_received: Instant::now(),
+            standings: HashMap::new(),
+            selected: None,
+            error: None,
+        }
+    }
+
+    // (seconds until start, seconds until end) counted down locally since the list arrived
+    pub fn countdown(&self, tournament: &Tournament) -> (i64, i64) {
+        let elapsed = self.received.elapsed().as_secs() as i64;
+        (tournament.starts_in - elapsed, tournament.ends_in - elapsed)
+    }
+
+    // seconds the entry invoice can still be paid for, 0 once the wallet has dropped it
+    pub fn invoice_left(&self, invoice: &TournamentInvoice) -> u64 {
+        invoice
+            .expires_in
+            .saturating_sub(self.invoice_received.elapsed().as_secs())
+    }
+}
+
+pub struct TournamentPlugin;
+
+impl Plugin for TournamentPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Tournaments::new())
+            .add_event::<TournamentUpdate>()
+            .add_systems(Update, apply_tournament_updates)
+            .add_systems(OnEnter(GameStage::Tournament), refresh_tournaments)
+            .add_systems(
+                Update,
+                tournament_menu.run_if(in_state(GameStage::Tournament)),
+            );
+    }
+}
+
+fn refresh_tournaments(
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut tournaments: ResMut<Tournaments>,
+) {
+    tournaments.error = None;
+    network_stuff.send(ClientMessage::ListTournaments);
+}
+
+fn apply_tournament_updates(
+    mut tournaments: ResMut<Tournaments>,
+    mut updates: EventReader<TournamentUpdate>,
+    mut pending_run: ResMut<PendingRun>,
+    mut next_state: ResMut<NextState<GameStage>>,
+    mut keyboard_state: ResMut<NextState<KeyboardState>>,
+) {
+    for update in updates.iter() {
+        match &update.0 {
+            TournamentMessage::List(list) => {
+                tournaments.list = list.clone();
+                tournaments.received = Instant::now();
+                if tournaments
+                    .selected
+                    .is_none_or(|id| !list.iter().any(|t| t.id == id))
+                {
+                    tournaments.selected = list.first().map(|t| t.id);
+                }
+            }
+            TournamentMessage::Invoice(invoice) => {
+                tournaments.invoice = Some(invoice.clone());
+                tournaments.invoice_received = Instant::now();
+                tournaments.error = None;
+            }
+            TournamentMessage::Registered(id) => {
+                if let Some(tournament) = tournaments.list.iter_mut().find(|t| t.id == *id) {
+                    tournament.registered = true;
+                }
+                if tournaments
+                    .invoice
+                    .as_ref()
+                    .is_some_and(|invoice| invoice.tournament_id == *id)
+                {
+                    tournaments.invoice = None;
+                }
+            }
+            TournamentMessage::Standings(standings) => {
+                tournaments
+                    .standings
+                    .insert(standings.tournament_id, standings.standings.clone());
+            }
+            TournamentMessage::Error(error) => {
+                tournaments.error = Some(error.clone());
+                // a turned down entry never gets its world, show why instead of an empty run
+                if matches!(pending_run.0, Some(FixedRun::Tournament(_))) {
+                    pending_run.0 = None;
+                    keyboard_state.set(KeyboardState::On);
+                    next_state.set(GameStage::Tournament);
+                }
+            }
+        }
+    }
+}
+
+fn status_text(tournaments: &Tournaments, tournament: &Tournament) -> String {
+    let (starts_in, ends_in) = tournaments.countdown(tournament);
+    match tournament.status {
+        TournamentStatus::Finished => "finished".to_string(),
+        _ if starts_in > 0 => format!("starts in {}", format_duration(starts_in)),
+        _ if ends_in > 0 => format!("ends in {}", format_duration(ends_in)),
+        _ => "closing…".to_string(),
+    }
+}
+
+// standings with what each paid place would win from the current pot
+fn tournament_board(ui: &mut egui::Ui, tournament: &Tournament, standings: &[(String, u64)]) {
+    let prizes = split_pot(tournament.pot_sats, &tournament.payout_split);
+
+    egui::Grid::new("tournament_board")
+        .striped(true)
+        .show(ui, |ui| {
+            for (place, (name, score)) in standings.iter().enumerate() {
+                ui.label(format!("{}:", place + 1));
+                ui.label(name);
+                ui.label(tournament.mode.format_high_score(*score));
+                match prizes.get(place) {
+                    Some(sats) => {
+                        ui.label(RichText::new(format!("⚡ {}", sats)).color(Color32::YELLOW))
+                    }
+                    None => ui.label(""),
+                };
+                ui.end_row();
+            }
+        });
+
+    if standings.is_empty() {
+        ui.label("No runs yet");
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn tournament_menu(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameStage>>,
+    mut keyboard_state: ResMut<NextState<KeyboardState>>,
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut tournaments: ResMut<Tournaments>,
+    mut selected_mode: ResMut<SelectedMode>,
+    mut player_name: ResMut<PlayerName>,
+    mut qr_codes: ResMut<QrCodes>,
+    mut query_player: Query<&mut Player>,
+    settings_menu: Res<SettingsMenu>,
+    mut pending_run: ResMut<PendingRun>,
+) {
+    if settings_menu.open {
+        return;
+    }
+
+    let mut pick = None;
+    let mut register = None;
+    let mut enter = None;
+
+    egui::Window::new("🏆 Tournaments")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Grid::new("tournament_list")
+                .striped(true)
+                .show(ui, |ui| {
+                    for tournament in tournaments.list.iter() {
+                        let selected = tournaments.selected == Some(tournament.id);
+                        if ui.selectable_label(selected, &tournament.name).clicked() {
+                            pick = Some(tournament.id);
+                        }
+                        ui.label(tournament.mode.title());
+                        ui.label(format!("⚡ {} entry", tournament.entry_sats));
+                        ui.label(status_text(&tournaments, tournament));
+                        ui.end_row();
+                    }
+                });
+
+            if tournaments.list.is_empty() {
+                ui.label("No tournaments scheduled");
+            }
+
+            if pick.is_some() {
+                tournaments.selected = pick;
+            }
+
+            if let Some(error) = &tournaments.error {
+                ui.colored_label(Color32::RED, error);
+            }
+
+            let Some(tournament) = tournaments
+                .selected
+                .and_then(|id| tournaments.list.iter().find(|t| t.id == id))
+            else {
+                return;
+            };
+
+            ui.separator();
+            ui.heading(&tournament.name);
+            ui.label(tournament.mode.description());
+            ui.label(format!(
+                "{} entrants, pot ⚡ {} sats, {}",
+                tournament.entrants,
+                tournament.pot_sats,
+                status_text(&tournaments, tournament)
+            ));
+
+            let (starts_in, ends_in) = tournaments.countdown(tournament);
+            let running =
+                tournament.status == TournamentStatus::Open && starts_in <= 0 && ends_in > 0;
+
+            if tournament.registered {
+                ui.label(RichText::new("✔ You're registered").color(Color32::GREEN));
+                if ui
+                    .add_enabled(
+                        running && !player_name.name.is_empty(),
+                        egui::Button::new("Play tournament run"),
+                    )
+                    .clicked()
+                {
+                    enter = Some((tournament.id, tournament.mode));
+                }
+                if player_name.name.is_empty() {
+                    ui.label("Pick a name in the menu first");
+                }
+            } else if tournament.status != TournamentStatus::Finished {
+                let invoice = tournaments
+                    .invoice
+                    .as_ref()
+                    .filter(|invoice| invoice.tournament_id == tournament.id);
+                let left = invoice.map_or(0, |invoice| tournaments.invoice_left(invoice));
+                match invoice.filter(|_| left > 0) {
+                    Some(invoice) => {
+                        let uri = format!("lightning:{}", invoice.bolt11);
+                        ui.horizontal(|ui| {
+                            qr_code(ui, &mut qr_codes, &uri, 160.0);
+                            ui.vertical(|ui| {
+                                ui.label(format!("Pay ⚡ {} sats to enter", tournament.entry_sats));
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label(format!(
+                                        "Waiting for payment… ({} left)",
+                                        format_duration(left as i64)
+                                    ));
+                                });
+                                if ui.button("Copy invoice").clicked() {
+                                    ui.output_mut(|o| o.copied_text = invoice.bolt11.clone());
+                                }
+                            });
+                        });
+                    }
+                    None => {
+                        if invoice.is_some() {
+                            ui.label(
+                                RichText::new("The entry invoice expired, register for a new one")
+                                    .color(Color32::LIGHT_RED),
+                            );
+                        }
+                        if ui
+                            .button(format!("Register (⚡ {} sats)", tournament.entry_sats))
+                            .clicked()
+                        {
+                            register = Some(tournament.id);
+                        }
+                    }
+                }
+            }
+
+            ui.separator();
+            ui.label("Standings");
+            let standings = tournaments
+                .standings
+                .get(&tournament.id)
+                .map(|s| s.as_slice())
+                .unwrap_or(&[]);
+            tournament_board(ui, tournament, standings);
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Refresh").clicked() {
+                    network_stuff.send(ClientMessage::ListTournaments);
+                }
+                if ui.button("Back").clicked() {
+                    next_state.set(GameStage::Menu);
+                }
+            });
+        });
+
+    if let Some(id) = register {
+        tournaments.error = None;
+        tournaments.invoice = None;
+        network_stuff.send(ClientMessage::RegisterTournament(id));
+    }
+
+    if let Some((id, mode)) = enter {
+        selected_mode.mode = mode;
+        keyboard_state.set(KeyboardState::Off);
+        player_name.submitted = true;
+        // the server answers with the tournament's world, which must not bounce us to the menu
+        pending_run.0 = Some(FixedRun::Tournament(id));
+        network_stuff.send(ClientMessage::SelectMode(mode));
+        network_stuff.send(ClientMessage::EnterTournament(id));
+        network_stuff.send(ClientMessage::PlayerName(player_name.name.clone()));
+
+        for mut player in query_player.iter_mut() {
+            player.spawn_time = Some(Instant::now());
+            player.name = player_name.name.clone();
+        }
+
+        next_state.set(GameStage::InGame);
+    }
+}
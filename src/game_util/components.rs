@@ -6,6 +6,15 @@ pub struct Rain;
 #[derive(Component)]
 pub struct Bolt;
 
+#[derive(Component, Default)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub gravity: f32,
+    pub life: f32,
+    pub max_life: f32,
+    pub color: Color,
+}
+
 #[derive(Component)]
 pub struct NamePlates {
     pub id: Uuid,
@@ -0,0 +1,71 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::network::messages::{
+    ChallengeMessage, LeaderboardPage, PrizeStatus, Profile, RoomInfo, TournamentMessage,
+};
+
+#[derive(Event)]
+pub struct CameraShake(pub f32);
+
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundEffect {
+    Pickup,
+    Hit,
+    Complete,
+    Click,
+    Key,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BurstKind {
+    Splash,
+    Sparks,
+    Lightning,
+}
+
+#[derive(Event)]
+pub struct ParticleBurst {
+    pub kind: BurstKind,
+    pub pos: Vec2,
+}
+
+#[derive(Event)]
+pub struct RoomJoined(pub RoomInfo);
+
+#[derive(Event)]
+pub struct PrizeUpdate(pub PrizeStatus);
+
+#[derive(Event)]
+pub struct TournamentUpdate(pub TournamentMessage);
+
+#[derive(Event)]
+pub struct ChallengeUpdate(pub ChallengeMessage);
+
+#[derive(Event)]
+pub struct LeaderboardUpdate(pub LeaderboardPage);
+
+#[derive(Event)]
+pub struct ProfileUpdate(pub Profile);
+
+// the local player's run is over, as reported by the server
+#[derive(Event)]
+pub struct RunFinished {
+    pub score: usize,
+    pub secs: u64,
+    pub won: bool,
+    pub by_rain: bool,
+}
+
+#[derive(SystemParam)]
+pub struct GameEvents<'w> {
+    pub camera_shake: EventWriter<'w, CameraShake>,
+    pub sound: EventWriter<'w, SoundEffect>,
+    pub particles: EventWriter<'w, ParticleBurst>,
+    pub room_joined: EventWriter<'w, RoomJoined>,
+    pub prize: EventWriter<'w, PrizeUpdate>,
+    pub tournament: EventWriter<'w, TournamentUpdate>,
+    pub challenge: EventWriter<'w, ChallengeUpdate>,
+    pub leaderboard: EventWriter<'w, LeaderboardUpdate>,
+    pub profile: EventWriter<'w, ProfileUpdate>,
+    pub run_finished: EventWriter<'w, RunFinished>,
+}
@@ -1,17 +1,24 @@
 use std::collections::VecDeque;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use futures::channel::mpsc::{Receiver, Sender};
 use uuid::Uuid;
 
-use crate::{game_core::objects::ObjectPos, network::messages::ClientMessage};
+use crate::{
+    game_core::objects::ObjectPos,
+    network::messages::{ClientMessage, GameMode},
+};
+
+use super::storage;
 
 #[derive(Resource)]
 pub struct Objects {
     pub rain_pos: Vec<ObjectPos>,
     pub bolt_pos: Vec<ObjectPos>,
     pub rng_seed: Option<u64>,
-    pub high_scores: Vec<(String, u64)>,
+    pub high_scores: HashMap<GameMode, Vec<(String, u64)>>,
+    pub team_scores: HashMap<u8, usize>,
+    pub pvp: bool,
 }
 
 impl Objects {
@@ -20,9 +27,18 @@ impl Objects {
             rain_pos: Vec::new(),
             bolt_pos: Vec::new(),
             rng_seed: None,
-            high_scores: Vec::new(),
+            high_scores: HashMap::new(),
+            team_scores: HashMap::new(),
+            pvp: false,
         }
     }
+
+    pub fn high_scores_for(&self, mode: GameMode) -> &[(String, u64)] {
+        self.high_scores
+            .get(&mode)
+            .map(|scores| scores.as_slice())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Resource)]
@@ -45,6 +61,13 @@ impl NetworkStuff {
             read: None,
         }
     }
+
+    pub fn send(&mut self, message: ClientMessage) {
+        match self.write.as_mut().unwrap().try_send(message) {
+            Ok(()) => {}
+            Err(e) => error!("Error sending message: {} CHANNEL FULL???", e),
+        };
+    }
 }
 
 #[derive(Resource)]
@@ -77,9 +100,13 @@ impl PingTimer {
     }
 }
 
+// the menu field, both keyboards and nostr profiles all cut names here
+pub const MAX_NAME_LENGTH: usize = 25;
+
 #[derive(Resource)]
 pub struct PlayerName {
     pub name: String,
+    pub ln_address: String,
     pub submitted: bool,
     pub id: Option<Uuid>,
 }
@@ -87,9 +114,27 @@ pub struct PlayerName {
 impl PlayerName {
     pub fn new() -> Self {
         Self {
-            name: "".to_string(),
+            name: storage::load("name").unwrap_or_default(),
+            ln_address: storage::load("ln_address").unwrap_or_default(),
             submitted: false,
             id: None,
         }
     }
 }
+
+// a fixed-seed run asked for from a menu, it starts with the server's next NewGame
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FixedRun {
+    Tournament(u32),
+    // day number, the world has to come on that day's seed
+    Daily(u64),
+}
+
+#[derive(Resource)]
+pub struct PendingRun(pub Option<FixedRun>);
+
+impl PendingRun {
+    pub fn new() -> Self {
+        Self(None)
+    }
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+// browser builds keep preferences in localStorage, native builds in a config folder
+
+#[cfg(target_arch = "wasm32")]
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    use gloo_storage::{LocalStorage, Storage};
+
+    LocalStorage::get(key).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    use gloo_storage::{LocalStorage, Storage};
+
+    if let Err(e) = LocalStorage::set(key, value) {
+        error!("Error saving {}: {:?}", key, e);
+    }
+}
+
+// the platform's per-user config folder, e.g. ~/.config/satrunner on linux
+#[cfg(not(target_arch = "wasm32"))]
+fn config_dir() -> std::path::PathBuf {
+    directories::ProjectDirs::from("run", "rain", "satrunner")
+        .map(|dirs| dirs.config_dir().to_path_buf())
+        .unwrap_or_else(|| std::path::PathBuf::from(".satrunner"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let path = config_dir().join(format!("{}.json", key));
+    let file = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&file).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    let dir = config_dir();
+    let path = dir.join(format!("{}.json", key));
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| serde_json::to_string_pretty(value).map_err(std::io::Error::from))
+        .and_then(|json| std::fs::write(path, json));
+
+    if let Err(e) = result {
+        error!("Error saving {}: {:?}", key, e);
+    }
+}
@@ -1,12 +1,12 @@
 use bevy::prelude::*;
 
-use crate::KeyboardState;
+use crate::{GameStage, KeyboardState};
 
 use self::{
     components::KeyBoard,
     layout::setup_keyboard,
     resources::CapitalizeToggle,
-    systems::{virtual_capitalize_system, virtual_keyboard_system},
+    systems::{physical_keyboard_system, virtual_capitalize_system, virtual_keyboard_system},
 };
 
 pub mod components;
@@ -28,6 +28,11 @@ impl Plugin for KeyboardPlugin {
                 (virtual_keyboard_system, virtual_capitalize_system)
                     .run_if(in_state(KeyboardState::On)),
             )
+            .add_systems(
+                Update,
+                physical_keyboard_system
+                    .run_if(in_state(KeyboardState::On).and_then(in_state(GameStage::Menu))),
+            )
             .add_systems(OnExit(KeyboardState::On), despawn_screen::<KeyBoard>);
     }
 }
@@ -1,6 +1,15 @@
 use bevy::prelude::*;
 
-use crate::{game_util::resources::PlayerName, keyboard::styles::PRESSED_BUTTON};
+use bevy_egui::EguiContexts;
+
+use crate::{
+    game_core::settings::SettingsMenu,
+    game_util::{
+        events::SoundEffect,
+        resources::{PlayerName, MAX_NAME_LENGTH},
+    },
+    keyboard::styles::PRESSED_BUTTON,
+};
 
 use super::{
     components::{Capitalizable, KeyBoardButton},
@@ -9,28 +18,31 @@ use super::{
 };
 
 const ACCEPTABLE_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz1234567890 @.";
-const MAX_INPUT_LENGTH: usize = 100;
-// #[allow(clippy::type_complexity)]
-// pub fn physical_keyboard_system(
-//     mut char_evr: EventReader<ReceivedCharacter>,
-//     keys: Res<Input<KeyCode>>,
+// typing on a real keyboard, unless egui already has focus on the name field
+pub fn physical_keyboard_system(
+    mut char_evr: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut contexts: EguiContexts,
+    settings_menu: Res<SettingsMenu>,
+    mut player_name: ResMut<PlayerName>,
+) {
+    if contexts.ctx_mut().wants_keyboard_input() || settings_menu.open {
+        char_evr.clear();
+        return;
+    }
 
-//     mut player_name: ResMut<PlayerName>,
-// ) {
-//     if keys.just_pressed(KeyCode::Back) {
-//         player_name.name.pop();
-//     }
+    if keys.just_pressed(KeyCode::Back) {
+        player_name.name.pop();
+    }
 
-//     for ev in char_evr.iter() {
-//         let k = ev.char;
+    for ev in char_evr.iter() {
+        let k = ev.char;
 
-//         if ACCEPTABLE_CHARS.contains(k) && player_name.name.len() < MAX_INPUT_LENGTH {
-//             player_name.name.push(k);
-//         } else {
-//             info!("no likey this character sorry")
-//         }
-//     }
-// }
+        if ACCEPTABLE_CHARS.contains(k) && player_name.name.len() < MAX_NAME_LENGTH {
+            player_name.name.push(k);
+        }
+    }
+}
 
 #[allow(clippy::type_complexity)]
 pub fn virtual_keyboard_system(
@@ -41,11 +53,13 @@ pub fn virtual_keyboard_system(
 
     mut player_name: ResMut<PlayerName>,
     mut c_toggle: ResMut<CapitalizeToggle>,
+    mut sound: EventWriter<SoundEffect>,
 ) {
     for (interaction, mut color, keyboard_button) in &mut interaction_query {
         let k = keyboard_button.0;
         match *interaction {
             Interaction::Pressed => {
+                sound.send(SoundEffect::Key);
                 match k {
                     '<' => {
                         player_name.name.pop();
@@ -55,7 +69,7 @@ pub fn virtual_keyboard_system(
                         debug!("capitalize is now set to: {}", c_toggle.0);
                     }
                     k if ACCEPTABLE_CHARS.contains(k)
-                        && player_name.name.len() < MAX_INPUT_LENGTH =>
+                        && player_name.name.len() < MAX_NAME_LENGTH =>
                     {
                         if c_toggle.0 {
                             player_name.name.push(k.to_ascii_uppercase());
@@ -63,9 +77,7 @@ pub fn virtual_keyboard_system(
                             player_name.name.push(k);
                         }
                     }
-                    _ => {
-                        info!("no likey this character sorry")
-                    }
+                    _ => {}
                 }
 
                 *color = PRESSED_BUTTON.into();
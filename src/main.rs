@@ -3,16 +3,39 @@ use bevy_ecs_ldtk::prelude::*;
 
 use bevy_egui::EguiPlugin;
 use game_core::{
+    audio::GameAudioPlugin,
+    camera::{camera_follow, spawn_camera},
+    challenge::ChallengePlugin,
+    daily::DailyPlugin,
     game_loop::{enemy_loop, player_loop, tick},
-    gui::{check_disconnected, disconnected, game_over, score_board, setup_menu},
+    gamepad::{gamepad_input, gamepad_menu_navigation},
+    gui::{check_disconnected, disconnected, game_over, keep_alive, score_board, setup_menu},
     handle::handle_server,
-    input::{input, update_joystick},
+    indicators::offscreen_indicators,
+    input::{input, keyboard_input, sync_joystick, update_joystick},
+    leaderboard::LeaderboardPlugin,
+    lobby::{apply_room_joined, lobby, refresh_rooms, Rooms},
+    minimap::{minimap, Minimap},
+    modes::SelectedMode,
+    nostr::NostrPlugin,
     objects::{handle_bolt, handle_rain},
+    particles::ParticlePlugin,
+    prize::PrizePlugin,
+    profile::ProfilePlugin,
+    pvp::player_bumps,
+    qr::QrCodes,
+    settings::SettingsPlugin,
+    spectate::{spectate, stop_spectating, Spectate},
     sprites::{pool_bolt, pool_rain, spawn_ldtk},
+    stats::StatsPlugin,
+    tournament::TournamentPlugin,
 };
 
-use game_util::resources::{
-    BoltPool, ClientTick, NetworkStuff, Objects, PingTimer, PlayerName, RainPool,
+use game_util::{
+    events::{CameraShake, RoomJoined},
+    resources::{
+        BoltPool, ClientTick, NetworkStuff, Objects, PendingRun, PingTimer, PlayerName, RainPool,
+    },
 };
 use keyboard::KeyboardPlugin;
 use network::websockets::websocket;
@@ -43,6 +66,17 @@ fn main() {
             EguiPlugin,
             LdtkPlugin,
             KeyboardPlugin,
+            GameAudioPlugin,
+            ParticlePlugin,
+            SettingsPlugin,
+            PrizePlugin,
+            TournamentPlugin,
+            ChallengePlugin,
+            DailyPlugin,
+            LeaderboardPlugin,
+            ProfilePlugin,
+            NostrPlugin,
+            StatsPlugin,
             VirtualJoystickPlugin::<String>::default(),
         ))
         .insert_resource(LevelSelection::Index(0))
@@ -56,19 +90,59 @@ fn main() {
         .register_ldtk_entity::<MyBundle>("background")
         .add_state::<GameStage>()
         .add_state::<KeyboardState>()
-        .add_systems(Startup, (spawn_ldtk, pool_rain, pool_bolt, websocket))
+        .add_event::<CameraShake>()
+        .add_event::<RoomJoined>()
+        .add_systems(
+            Startup,
+            (spawn_camera, spawn_ldtk, pool_rain, pool_bolt, websocket),
+        )
+        .add_systems(
+            Update,
+            (camera_follow, gamepad_menu_navigation, sync_joystick),
+        )
         .add_systems(Update, setup_menu.run_if(in_state(GameStage::Menu)))
-        .add_systems(Update, (handle_server, score_board, check_disconnected))
-        .add_systems(FixedUpdate, (tick, enemy_loop, handle_rain, handle_bolt))
-        .add_systems(Update, (input).run_if(in_state(GameStage::InGame)))
+        .add_systems(OnEnter(GameStage::Lobby), refresh_rooms)
+        .add_systems(Update, lobby.run_if(in_state(GameStage::Lobby)))
+        .add_systems(
+            Update,
+            (
+                handle_server,
+                apply_room_joined,
+                score_board,
+                check_disconnected,
+            ),
+        )
+        .add_systems(
+            FixedUpdate,
+            (tick, enemy_loop, handle_rain, handle_bolt, keep_alive),
+        )
+        .add_systems(
+            Update,
+            (
+                input,
+                keyboard_input,
+                gamepad_input,
+                update_joystick,
+                offscreen_indicators,
+            )
+                .run_if(in_state(GameStage::InGame)),
+        )
         .add_systems(
             Update,
             (disconnected).run_if(in_state(GameStage::Disconnected)),
         )
         .add_systems(Update, (game_over).run_if(in_state(GameStage::GameOver)))
+        .add_systems(Update, (spectate).run_if(in_state(GameStage::Spectating)))
+        .add_systems(OnExit(GameStage::Spectating), stop_spectating)
+        .add_systems(
+            Update,
+            minimap.run_if(in_state(GameStage::InGame).or_else(in_state(GameStage::Spectating))),
+        )
         .add_systems(
             FixedUpdate,
-            (player_loop).run_if(in_state(GameStage::InGame)),
+            (player_loop, player_bumps)
+                .chain()
+                .run_if(in_state(GameStage::InGame)),
         )
         .insert_resource(FixedTime::new_from_secs(TICK_RATE))
         .insert_resource(ClearColor(Color::BLACK))
@@ -79,6 +153,12 @@ fn main() {
         .insert_resource(ClientTick::new())
         .insert_resource(PlayerName::new())
         .insert_resource(PingTimer::new())
+        .insert_resource(PendingRun::new())
+        .insert_resource(SelectedMode::new())
+        .insert_resource(Rooms::new())
+        .insert_resource(QrCodes::new())
+        .insert_resource(Spectate::new())
+        .insert_resource(Minimap::new())
         .run();
 }
 
@@ -86,9 +166,15 @@ fn main() {
 pub enum GameStage {
     #[default]
     Menu,
+    Lobby,
     InGame,
     Disconnected,
     GameOver,
+    Spectating,
+    Tournament,
+    Leaderboard,
+    Profile,
+    Stats,
 }
 
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
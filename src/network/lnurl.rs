@@ -0,0 +1,70 @@
+use futures::channel::oneshot;
+use gloo_net::http::Request;
+use satrunner_payout::lnurl::{invoice_url, parse_invoice_response, parse_pay_response};
+use wasm_bindgen_futures::spawn_local;
+
+pub use satrunner_payout::lnurl::{LightningAddress, LnurlError, PayParams};
+
+// the payout crate's lookups need a Send http client, the browser one isn't
+async fn get(url: &str) -> Result<String, LnurlError> {
+    Request::get(url)
+        .send()
+        .await
+        .map_err(|e| LnurlError::Http(e.to_string()))?
+        .text()
+        .await
+        .map_err(|e| LnurlError::Http(e.to_string()))
+}
+
+async fn fetch_pay_params(
+    address: &LightningAddress,
+    endpoint: &str,
+) -> Result<PayParams, LnurlError> {
+    parse_pay_response(&get(&address.lnurlp_url(endpoint)).await?)
+}
+
+pub fn resolve(
+    address: &LightningAddress,
+    endpoint: &str,
+) -> oneshot::Receiver<Result<PayParams, LnurlError>> {
+    let (tx, rx) = oneshot::channel();
+
+    let address = address.clone();
+    let endpoint = endpoint.to_string();
+
+    spawn_local(async move {
+        let result = fetch_pay_params(&address, &endpoint).await;
+
+        // nobody is waiting any more if the game over screen was left
+        let _ = tx.send(result);
+    });
+
+    rx
+}
+
+async fn fetch_invoice(
+    address: &LightningAddress,
+    endpoint: &str,
+    sats: u64,
+) -> Result<String, LnurlError> {
+    let params = fetch_pay_params(address, endpoint).await?;
+    parse_invoice_response(&get(&invoice_url(&params, sats * 1000)?).await?)
+}
+
+// a bolt11 invoice of `sats` to pay straight to `address`
+pub fn invoice(
+    address: &LightningAddress,
+    endpoint: &str,
+    sats: u64,
+) -> oneshot::Receiver<Result<String, LnurlError>> {
+    let (tx, rx) = oneshot::channel();
+
+    let address = address.clone();
+    let endpoint = endpoint.to_string();
+
+    spawn_local(async move {
+        let _ = tx.send(fetch_invoice(&address, &endpoint, sats).await);
+    });
+
+    rx
+}
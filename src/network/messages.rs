@@ -11,12 +11,59 @@ pub enum NetworkMessage {
     DamagePlayer(Damage),
     ScoreUpdate(Score),
     SyncClient(SyncMessage),
+    RoomList(Vec<RoomInfo>),
+    RoomJoined(RoomInfo),
+    RoomError(String),
+    Pong,
+    TeamAssigned(u8),
+    BoltStolen(Steal),
+    PrizeClaim(PrizeStatus),
+    Tournament(TournamentMessage),
+    Challenge(ChallengeMessage),
+    Leaderboard(LeaderboardPage),
+    Profile(Profile),
 }
 
 #[derive(Readable, Writable, Debug, Clone)]
 pub enum ClientMessage {
     PlayerInput(PlayerInput),
     PlayerName(String),
+    SelectMode(GameMode),
+    CreateRoom(CreateRoom),
+    JoinRoom(String),
+    LeaveRoom,
+    ListRooms,
+    Ping,
+    // the server puts the player on the smaller team and answers with TeamAssigned
+    JoinTeam,
+    Spectate(bool),
+    ClaimPrize(String),
+    ListTournaments,
+    RegisterTournament(u32),
+    EnterTournament(u32),
+    GetChallenge,
+    // day number since the unix epoch, a second official attempt gets ChallengeMessage::DailyRejected
+    PlayDaily(u64),
+    Leaderboard(LeaderboardQuery),
+    // persistent token identifying the player across connections, sent first on connect
+    Identify(String),
+    GetProfile,
+    // hex public key, the server checks it against signed scores
+    NostrLogin(String),
+    // signed score event as json
+    NostrScore(String),
+    // unbinds the public key from this connection, later scores go out unsigned
+    NostrLogout,
+}
+
+#[derive(Readable, Writable, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameMode {
+    #[default]
+    TimeAttack,
+    Endless,
+    ScoreAttack,
+    LastStanding,
+    Teams,
 }
 
 #[derive(Readable, Writable, Debug, Clone, Default)]
@@ -57,8 +104,9 @@ pub struct NewGame {
     pub id: Uuid,
     pub server_tick: u64,
     pub rng_seed: u64,
-    pub high_scores: Vec<(String, u64)>,
+    pub high_scores: Vec<(GameMode, Vec<(String, u64)>)>,
     pub objects: ObjectMsg,
+    pub pvp: bool,
 }
 
 #[derive(Readable, Writable, Debug, Clone)]
@@ -72,9 +120,10 @@ pub struct Damage {
     pub id: Uuid,
     pub tick: Option<u64>,
     pub secs_alive: u64,
-    pub high_scores: Option<Vec<(String, u64)>>,
+    pub high_scores: Option<Vec<(GameMode, Vec<(String, u64)>)>>,
     pub pos: [f32; 2],
     pub score: usize,
+    pub won: bool,
 }
 
 #[derive(Readable, Writable, Debug, Clone)]
@@ -82,6 +131,7 @@ pub struct Score {
     pub id: Uuid,
     pub score: usize,
     pub tick: u64,
+    pub team_score: Option<(u8, usize)>,
 }
 
 #[derive(Readable, Writable, Debug, Clone)]
@@ -93,4 +143,185 @@ pub struct PlayerState {
     pub id: Uuid,
     pub time_alive: u64,
     pub alive: bool,
+    pub mode: GameMode,
+    pub team: Option<u8>,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct CreateRoom {
+    pub mode: GameMode,
+    pub private: bool,
+    pub pvp: bool,
+    // lightning address players can tip the host at
+    pub host_address: Option<String>,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct RoomInfo {
+    pub code: String,
+    pub mode: GameMode,
+    pub players: u32,
+    pub max_players: u32,
+    pub private: bool,
+    pub pvp: bool,
+    pub host_address: Option<String>,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct Steal {
+    pub thief: Uuid,
+    pub victim: Uuid,
+    pub thief_score: usize,
+    pub victim_score: usize,
+    pub tick: u64,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub enum PrizeStatus {
+    Paid(u64),
+    Rejected(String),
+}
+
+#[derive(Readable, Writable, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TournamentStatus {
+    Upcoming,
+    Open,
+    Finished,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct Tournament {
+    pub id: u32,
+    pub name: String,
+    pub mode: GameMode,
+    pub status: TournamentStatus,
+    pub entry_sats: u64,
+    pub pot_sats: u64,
+    pub entrants: u32,
+    // percent of the pot for each place, best first
+    pub payout_split: Vec<u8>,
+    // seconds from when the server sent this, negative once passed
+    pub starts_in: i64,
+    pub ends_in: i64,
+    pub registered: bool,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct TournamentInvoice {
+    pub tournament_id: u32,
+    pub bolt11: String,
+    pub expires_in: u64,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct TournamentStandings {
+    pub tournament_id: u32,
+    // name and mode score, best first
+    pub standings: Vec<(String, u64)>,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub enum TournamentMessage {
+    List(Vec<Tournament>),
+    Invoice(TournamentInvoice),
+    Registered(u32),
+    Standings(TournamentStandings),
+    Error(String),
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct ChallengeSchedule {
+    pub week: u32,
+    pub mode: GameMode,
+    // unix seconds
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub server_time: u64,
+    // hex sha256 of the week's rng seed, the seed itself is revealed once the week is over
+    pub seed_commitment: String,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct ChallengeWeek {
+    pub week: u32,
+    pub mode: GameMode,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub seed: u64,
+    pub seed_commitment: String,
+    // name and mode score, best first
+    pub winners: Vec<(String, u64)>,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub enum ChallengeMessage {
+    Schedule(ChallengeSchedule),
+    Archive(Vec<ChallengeWeek>),
+    DailyResult(DailyResult),
+    // answer to PlayDaily when this identity already played that day, the run stays a normal game
+    DailyRejected(u64),
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct DailyResult {
+    pub day: u64,
+    pub rank: u32,
+    pub players: u32,
+}
+
+#[derive(Readable, Writable, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LeaderboardPeriod {
+    #[default]
+    AllTime,
+    Weekly,
+    Daily,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct LeaderboardQuery {
+    pub mode: GameMode,
+    pub period: LeaderboardPeriod,
+    pub page: u32,
+    // the server looks up this player's rank so it can be shown off the page
+    pub name: String,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    pub name: String,
+    pub score: u64,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct LeaderboardPage {
+    pub mode: GameMode,
+    pub period: LeaderboardPeriod,
+    pub page: u32,
+    // total entries on the board, not pages
+    pub total: u32,
+    pub entries: Vec<LeaderboardEntry>,
+    pub own: Option<LeaderboardEntry>,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct RunRecord {
+    pub mode: GameMode,
+    pub score: u64,
+    pub secs: u64,
+    pub won: bool,
+    // unix seconds
+    pub played_at: u64,
+}
+
+#[derive(Readable, Writable, Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    // unix seconds
+    pub created_at: u64,
+    pub server_time: u64,
+    // best leaderboard score per mode
+    pub personal_bests: Vec<(GameMode, u64)>,
+    // most recent first
+    pub history: Vec<RunRecord>,
 }
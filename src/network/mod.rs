@@ -1,2 +1,4 @@
+pub mod lnurl;
 pub mod messages;
+pub mod nostr;
 pub mod websockets;
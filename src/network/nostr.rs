@@ -0,0 +1,513 @@
+use std::fmt;
+
+use futures::{
+    channel::oneshot,
+    future::{self, Either},
+    Future, SinkExt, StreamExt,
+};
+use gloo_net::websocket::{futures::WebSocket, Message};
+use gloo_timers::future::TimeoutFuture;
+use k256::schnorr::{Signature, SigningKey, VerifyingKey};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use wasm_bindgen_futures::spawn_local;
+
+// NIP-01 short text note, so scores show up in any client following the tag
+pub const SCORE_KIND: u32 = 1;
+pub const SCORE_TAG: &str = "rainrun";
+const PROFILE_SUBSCRIPTION: &str = "rainrun-profile";
+// a relay that takes the socket but never answers gives up after this
+const RELAY_TIMEOUT_MS: u32 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NostrError {
+    NoExtension,
+    Extension(String),
+    InvalidKey,
+    BadSignature,
+    Relay(String),
+}
+
+impl fmt::Display for NostrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NostrError::NoExtension => write!(f, "no nostr extension (NIP-07) found"),
+            NostrError::Extension(e) => write!(f, "extension said: {}", e),
+            NostrError::InvalidKey => write!(f, "invalid nostr key"),
+            NostrError::BadSignature => write!(f, "event signature doesn't verify"),
+            NostrError::Relay(e) => write!(f, "relay: {}", e),
+        }
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn unix_now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UnsignedEvent {
+    pub pubkey: String,
+    pub created_at: u64,
+    pub kind: u32,
+    pub tags: Vec<Vec<String>>,
+    pub content: String,
+}
+
+impl UnsignedEvent {
+    // NIP-01 id: sha256 of the compact json array
+    pub fn id(&self) -> [u8; 32] {
+        let serialized = json!([
+            0,
+            self.pubkey,
+            self.created_at,
+            self.kind,
+            self.tags,
+            self.content
+        ])
+        .to_string();
+        Sha256::digest(serialized.as_bytes()).into()
+    }
+
+    fn into_signed(self, id: [u8; 32], sig: &[u8]) -> Event {
+        Event {
+            id: to_hex(&id),
+            pubkey: self.pubkey,
+            created_at: self.created_at,
+            kind: self.kind,
+            tags: self.tags,
+            content: self.content,
+            sig: to_hex(sig),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Event {
+    pub id: String,
+    pub pubkey: String,
+    pub created_at: u64,
+    pub kind: u32,
+    pub tags: Vec<Vec<String>>,
+    pub content: String,
+    pub sig: String,
+}
+
+impl Event {
+    // anyone can check this, which is what makes the published board trustworthy
+    pub fn verify(&self) -> bool {
+        let unsigned = UnsignedEvent {
+            pubkey: self.pubkey.clone(),
+            created_at: self.created_at,
+            kind: self.kind,
+            tags: self.tags.clone(),
+            content: self.content.clone(),
+        };
+        let id = unsigned.id();
+        if to_hex(&id) != self.id {
+            return false;
+        }
+
+        let (Some(pubkey), Some(sig)) = (from_hex(&self.pubkey), from_hex(&self.sig)) else {
+            return false;
+        };
+        let (Ok(key), Ok(sig)) = (
+            VerifyingKey::from_bytes(&pubkey),
+            Signature::try_from(sig.as_slice()),
+        ) else {
+            return false;
+        };
+        key.verify_raw(&id, &sig).is_ok()
+    }
+}
+
+// a key kept by the game itself, for platforms without a signing extension
+#[derive(Clone)]
+pub struct LocalKey(SigningKey);
+
+impl LocalKey {
+    pub fn generate() -> Self {
+        // a random scalar is out of range with negligible odds, just draw again
+        loop {
+            let bytes: [u8; 32] = rand::thread_rng().gen();
+            if let Ok(key) = SigningKey::from_bytes(&bytes) {
+                return Self(key);
+            }
+        }
+    }
+
+    pub fn from_hex(secret: &str) -> Result<Self, NostrError> {
+        let bytes = from_hex(secret.trim()).ok_or(NostrError::InvalidKey)?;
+        SigningKey::from_bytes(&bytes)
+            .map(Self)
+            .map_err(|_| NostrError::InvalidKey)
+    }
+
+    pub fn secret_hex(&self) -> String {
+        to_hex(&self.0.to_bytes())
+    }
+
+    pub fn public_key(&self) -> String {
+        to_hex(&self.0.verifying_key().to_bytes())
+    }
+
+    pub fn sign(&self, event: UnsignedEvent) -> Result<Event, NostrError> {
+        let id = event.id();
+        let aux: [u8; 32] = rand::thread_rng().gen();
+        let sig = self
+            .0
+            .sign_raw(&id, &aux)
+            .map_err(|_| NostrError::BadSignature)?;
+        Ok(event.into_signed(id, &sig.to_bytes()))
+    }
+}
+
+#[derive(Clone)]
+pub enum Signer {
+    // NIP-07 `window.nostr`, the key never leaves the extension
+    Extension,
+    Local(LocalKey),
+}
+
+impl Signer {
+    pub async fn public_key(&self) -> Result<String, NostrError> {
+        match self {
+            Signer::Extension => nip07::get_public_key().await,
+            Signer::Local(key) => Ok(key.public_key()),
+        }
+    }
+
+    pub async fn sign(&self, event: UnsignedEvent) -> Result<Event, NostrError> {
+        let event = match self {
+            Signer::Extension => nip07::sign_event(&event).await?,
+            Signer::Local(key) => key.sign(event)?,
+        };
+        // extensions are third party code, don't pass on something that won't verify
+        if event.verify() {
+            Ok(event)
+        } else {
+            Err(NostrError::BadSignature)
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod nip07 {
+    use js_sys::{Function, Promise, Reflect, JSON};
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+
+    use super::{Event, NostrError, UnsignedEvent};
+
+    fn js_error(value: JsValue) -> NostrError {
+        NostrError::Extension(
+            value
+                .as_string()
+                .or_else(|| JSON::stringify(&value).ok().map(String::from))
+                .unwrap_or_else(|| "unknown error".to_string()),
+        )
+    }
+
+    async fn call(method: &str, arg: Option<JsValue>) -> Result<JsValue, NostrError> {
+        let window = web_sys::window().ok_or(NostrError::NoExtension)?;
+        let nostr = Reflect::get(&window, &JsValue::from_str("nostr"))
+            .ok()
+            .filter(|nostr| !nostr.is_undefined() && !nostr.is_null())
+            .ok_or(NostrError::NoExtension)?;
+        let function: Function = Reflect::get(&nostr, &JsValue::from_str(method))
+            .ok()
+            .and_then(|function| function.dyn_into().ok())
+            .ok_or(NostrError::NoExtension)?;
+
+        let promise = match arg {
+            Some(arg) => function.call1(&nostr, &arg),
+            None => function.call0(&nostr),
+        }
+        .map_err(js_error)?;
+
+        JsFuture::from(promise.unchecked_into::<Promise>())
+            .await
+            .map_err(js_error)
+    }
+
+    pub async fn get_public_key() -> Result<String, NostrError> {
+        call("getPublicKey", None)
+            .await?
+            .as_string()
+            .ok_or_else(|| NostrError::Extension("no public key returned".to_string()))
+    }
+
+    pub async fn sign_event(event: &UnsignedEvent) -> Result<Event, NostrError> {
+        let json =
+            serde_json::to_string(event).map_err(|e| NostrError::Extension(e.to_string()))?;
+        let signed = call("signEvent", Some(JSON::parse(&json).map_err(js_error)?)).await?;
+        let signed: String = JSON::stringify(&signed).map_err(js_error)?.into();
+
+        serde_json::from_str(&signed).map_err(|e| NostrError::Extension(e.to_string()))
+    }
+}
+
+// native builds have no browser extension to talk to
+#[cfg(not(target_arch = "wasm32"))]
+mod nip07 {
+    use super::{Event, NostrError, UnsignedEvent};
+
+    pub async fn get_public_key() -> Result<String, NostrError> {
+        Err(NostrError::NoExtension)
+    }
+
+    pub async fn sign_event(_event: &UnsignedEvent) -> Result<Event, NostrError> {
+        Err(NostrError::NoExtension)
+    }
+}
+
+async fn open_relay(relay: &str) -> Result<WebSocket, NostrError> {
+    WebSocket::open(relay).map_err(|e| NostrError::Relay(e.to_string()))
+}
+
+async fn send_text(ws: &mut WebSocket, value: serde_json::Value) -> Result<(), NostrError> {
+    ws.send(Message::Text(value.to_string()))
+        .await
+        .map_err(|e| NostrError::Relay(e.to_string()))
+}
+
+async fn with_timeout<T>(
+    conversation: impl Future<Output = Result<T, NostrError>>,
+) -> Result<T, NostrError> {
+    let conversation = Box::pin(conversation);
+    match future::select(conversation, TimeoutFuture::new(RELAY_TIMEOUT_MS)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(NostrError::Relay("timed out".to_string())),
+    }
+}
+
+// waits for the relay's NIP-20 OK for this event
+pub async fn publish(relay: &str, event: &Event) -> Result<(), NostrError> {
+    let mut ws = open_relay(relay).await?;
+    let result = with_timeout(async {
+        send_text(&mut ws, json!(["EVENT", event])).await?;
+        wait_for_ok(&mut ws, event).await
+    })
+    .await;
+
+    let _ = ws.close(None, None);
+    result
+}
+
+async fn wait_for_ok(ws: &mut WebSocket, event: &Event) -> Result<(), NostrError> {
+    while let Some(message) = ws.next().await {
+        let Ok(Message::Text(text)) = message else {
+            continue;
+        };
+        let Ok(reply) = serde_json::from_str::<serde_json::Value>(&text) else {
+            continue;
+        };
+        if reply[0] == "OK" && reply[1] == event.id.as_str() {
+            return if reply[2].as_bool() == Some(true) {
+                Ok(())
+            } else {
+                Err(NostrError::Relay(
+                    reply[3].as_str().unwrap_or("rejected").to_string(),
+                ))
+            };
+        }
+    }
+
+    Err(NostrError::Relay("closed before confirming".to_string()))
+}
+
+// display name from the author's kind 0 metadata, if the relay has any
+pub async fn fetch_name(relay: &str, pubkey: &str) -> Result<Option<String>, NostrError> {
+    let mut ws = open_relay(relay).await?;
+    let result = with_timeout(read_profile_name(&mut ws, pubkey)).await;
+
+    let _ = ws.close(None, None);
+    result
+}
+
+async fn read_profile_name(ws: &mut WebSocket, pubkey: &str) -> Result<Option<String>, NostrError> {
+    send_text(
+        ws,
+        json!([
+            "REQ",
+            PROFILE_SUBSCRIPTION,
+            { "kinds": [0], "authors": [pubkey], "limit": 1 }
+        ]),
+    )
+    .await?;
+
+    let mut name = None;
+    while let Some(message) = ws.next().await {
+        let Ok(Message::Text(text)) = message else {
+            continue;
+        };
+        let Ok(reply) = serde_json::from_str::<serde_json::Value>(&text) else {
+            continue;
+        };
+        if reply[1] != PROFILE_SUBSCRIPTION {
+            continue;
+        }
+        if reply[0] == "EVENT" {
+            let metadata = reply[2]["content"]
+                .as_str()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(content).ok());
+            name = metadata.and_then(|metadata| {
+                ["display_name", "name"]
+                    .iter()
+                    .filter_map(|key| metadata[*key].as_str())
+                    .find(|name| !name.trim().is_empty())
+                    .map(|name| name.trim().to_string())
+            });
+        } else if reply[0] == "EOSE" {
+            break;
+        }
+    }
+
+    let _ = send_text(ws, json!(["CLOSE", PROFILE_SUBSCRIPTION])).await;
+    Ok(name)
+}
+
+// public key plus the profile name lookup, which is skipped without a relay
+pub type LoginResult = Result<(String, Result<Option<String>, NostrError>), NostrError>;
+
+pub fn login(signer: Signer, relay: &str) -> oneshot::Receiver<LoginResult> {
+    let (tx, rx) = oneshot::channel();
+    let relay = relay.trim().to_string();
+
+    spawn_local(async move {
+        let result = async {
+            let pubkey = signer.public_key().await?;
+            // a relay failure shouldn't stop the login, it's only reported
+            let name = if relay.is_empty() {
+                Ok(None)
+            } else {
+                fetch_name(&relay, &pubkey).await
+            };
+            Ok::<_, NostrError>((pubkey, name))
+        }
+        .await;
+
+        let _ = tx.send(result);
+    });
+
+    rx
+}
+
+pub fn sign(signer: Signer, event: UnsignedEvent) -> oneshot::Receiver<Result<Event, NostrError>> {
+    let (tx, rx) = oneshot::channel();
+
+    spawn_local(async move {
+        let _ = tx.send(signer.sign(event).await);
+    });
+
+    rx
+}
+
+pub fn publish_to(relay: &str, event: Event) -> oneshot::Receiver<Result<(), NostrError>> {
+    let (tx, rx) = oneshot::channel();
+    let relay = relay.trim().to_string();
+
+    spawn_local(async move {
+        let _ = tx.send(publish(&relay, &event).await);
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP-340 test vector 0, secret key 3
+    const SECRET: &str = "0000000000000000000000000000000000000000000000000000000000000003";
+    const PUBKEY: &str = "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+
+    fn score_event() -> UnsignedEvent {
+        UnsignedEvent {
+            pubkey: PUBKEY.to_string(),
+            created_at: 1700000000,
+            kind: SCORE_KIND,
+            tags: vec![vec!["t".to_string(), SCORE_TAG.to_string()]],
+            content: "ran 21 bolts in 01:15".to_string(),
+        }
+    }
+
+    #[test]
+    fn event_id_is_the_nip01_hash() {
+        assert_eq!(
+            to_hex(&score_event().id()),
+            "1676e29d30bd192eb5ade98976f6924ccb0452d6671d7fbd3c30420dde090f30"
+        );
+    }
+
+    #[test]
+    fn local_key_signs_verifiable_events() {
+        let key = LocalKey::from_hex(SECRET).unwrap();
+        assert_eq!(key.public_key(), PUBKEY);
+        assert_eq!(key.secret_hex(), SECRET);
+
+        let event = key.sign(score_event()).unwrap();
+        assert_eq!(event.id, to_hex(&score_event().id()));
+        assert!(event.verify());
+
+        let generated = LocalKey::generate();
+        let mut unsigned = score_event();
+        unsigned.pubkey = generated.public_key();
+        assert!(generated.sign(unsigned).unwrap().verify());
+    }
+
+    #[test]
+    fn tampered_events_fail_to_verify() {
+        let key = LocalKey::from_hex(SECRET).unwrap();
+        let event = key.sign(score_event()).unwrap();
+
+        let mut content = event.clone();
+        content.content = "ran 99 bolts in 00:01".to_string();
+        assert!(!content.verify());
+
+        // a matching id doesn't help when the signature is someone else's
+        let mut sig = event.clone();
+        sig.sig
+            .replace_range(0..2, if &sig.sig[0..2] == "00" { "01" } else { "00" });
+        assert!(!sig.verify());
+
+        let mut pubkey = event;
+        pubkey.pubkey = LocalKey::generate().public_key();
+        assert!(!pubkey.verify());
+    }
+
+    #[test]
+    fn hex_keys_must_be_32_bytes() {
+        assert_eq!(from_hex("0a1B"), Some(vec![0x0a, 0x1b]));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+
+        assert!(LocalKey::from_hex(&SECRET[2..]).is_err());
+        assert!(LocalKey::from_hex(&format!("{}00", SECRET)).is_err());
+        assert!(LocalKey::from_hex(&SECRET[1..]).is_err());
+    }
+}
@@ -6,18 +6,32 @@ use gloo_net::websocket::{futures::WebSocket, Message};
 use speedy::Writable;
 use wasm_bindgen_futures::spawn_local;
 
-use crate::game_util::resources::{NetworkStuff, PingTimer};
+use crate::{
+    game_core::{profile::Identity, settings::Settings},
+    game_util::resources::{NetworkStuff, PingTimer},
+    GameStage,
+};
 
 use super::messages::ClientMessage;
 
 // pub const DELAY: u32 = 500;
 // use gloo_timers::future::TimeoutFuture;
 
-pub fn websocket(mut network_stuff: ResMut<NetworkStuff>, mut ping: ResMut<PingTimer>) {
-    #[cfg(debug_assertions)]
-    let ws = WebSocket::open("ws://0.0.0.0:3030/run").unwrap();
-    #[cfg(not(debug_assertions))]
-    let ws = WebSocket::open("wss://satrunner.gg/run").unwrap();
+pub fn websocket(
+    mut network_stuff: ResMut<NetworkStuff>,
+    mut ping: ResMut<PingTimer>,
+    settings: Res<Settings>,
+    identity: Res<Identity>,
+    mut next_state: ResMut<NextState<GameStage>>,
+) {
+    let ws = match WebSocket::open(&settings.server) {
+        Ok(ws) => ws,
+        Err(e) => {
+            error!("Error connecting to {}: {:?}", settings.server, e);
+            next_state.set(GameStage::Disconnected);
+            return;
+        }
+    };
     let (mut write, mut read) = ws.split();
 
     let (send_tx, mut send_rx) = futures::channel::mpsc::channel::<ClientMessage>(1000);
@@ -27,6 +41,8 @@ pub fn websocket(mut network_stuff: ResMut<NetworkStuff>, mut ping: ResMut<PingT
     let mut cancel_tx_clone = cancel_tx.clone();
 
     network_stuff.write = Some(send_tx);
+    // queued ahead of everything else so the server knows who this is before the game starts
+    network_stuff.send(ClientMessage::Identify(identity.token.clone()));
     network_stuff.read = Some(read_rx);
     ping.disconnected_rx = Some(cancel_rx);
     ping.disconnected_tx = Some(cancel_tx);